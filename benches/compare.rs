@@ -0,0 +1,134 @@
+//! This crate against other text encodings on identical inputs: `cargo bench --bench compare`.
+//!
+//! Before timing, prints each encoding's output size and the QR code data bits it needs: Base45
+//! and upper-case hex fit the alphanumeric mode (5.5 bits per character), Base64 needs the byte
+//! mode (8 bits per character).
+
+use std::hint::black_box;
+
+use base64::Engine;
+use criterion::{BenchmarkId, Criterion, Throughput, criterion_group, criterion_main};
+
+/// Payload sizes in bytes: a typical QR payload and a bulk buffer.
+const SIZES: [usize; 2] = [1024, 64 * 1024];
+
+fn payload(len: usize) -> Vec<u8> {
+    (0..len as u32)
+        .map(|i| (i.wrapping_mul(2654435761) >> 13) as u8)
+        .collect()
+}
+
+/// QR data bits for `text`: alphanumeric mode packs 2 characters into 11 bits when every
+/// character is in the QR alphanumeric set, byte mode takes 8 bits per character otherwise.
+fn qr_bits(text: &str) -> usize {
+    let alphanumeric = text
+        .bytes()
+        .all(|b| qr_base45::BASE45_ALPHABET.contains(&b));
+    if alphanumeric {
+        text.len() / 2 * 11 + text.len() % 2 * 6
+    } else {
+        text.len() * 8
+    }
+}
+
+fn print_sizes() {
+    let data = payload(1024);
+    println!(
+        "{:<22} {:>6} {:>8}",
+        "encoding of 1 KiB", "chars", "QR bits"
+    );
+    for (name, text) in [
+        ("qr-base45", qr_base45::encode(&data)),
+        ("base45", base45::encode(&data)),
+        (
+            "base64",
+            base64::engine::general_purpose::STANDARD.encode(&data),
+        ),
+        (
+            "data-encoding HEXUPPER",
+            data_encoding::HEXUPPER.encode(&data),
+        ),
+    ] {
+        println!("{name:<22} {:>6} {:>8}", text.len(), qr_bits(&text));
+    }
+}
+
+fn encode(c: &mut Criterion) {
+    print_sizes();
+    let mut group = c.benchmark_group("compare_encode");
+    for len in SIZES {
+        let data = payload(len);
+        group.throughput(Throughput::Bytes(len as u64));
+        group.bench_with_input(BenchmarkId::new("qr-base45", len), &data, |b, data| {
+            b.iter(|| qr_base45::encode(black_box(data)))
+        });
+        group.bench_with_input(BenchmarkId::new("base45", len), &data, |b, data| {
+            b.iter(|| base45::encode(black_box(data)))
+        });
+        group.bench_with_input(BenchmarkId::new("base64", len), &data, |b, data| {
+            b.iter(|| base64::engine::general_purpose::STANDARD.encode(black_box(data)))
+        });
+        group.bench_with_input(
+            BenchmarkId::new("data-encoding base64", len),
+            &data,
+            |b, data| b.iter(|| data_encoding::BASE64.encode(black_box(data))),
+        );
+        group.bench_with_input(
+            BenchmarkId::new("data-encoding hex", len),
+            &data,
+            |b, data| b.iter(|| data_encoding::HEXUPPER.encode(black_box(data))),
+        );
+    }
+    group.finish();
+}
+
+fn decode(c: &mut Criterion) {
+    let mut group = c.benchmark_group("compare_decode");
+    for len in SIZES {
+        let data = payload(len);
+        // Throughput counts decoded bytes, so encodings of different density compare directly
+        group.throughput(Throughput::Bytes(len as u64));
+        let text = qr_base45::encode(&data);
+        group.bench_with_input(BenchmarkId::new("qr-base45", len), &text, |b, text| {
+            b.iter(|| qr_base45::decode(black_box(text)).unwrap())
+        });
+        group.bench_with_input(BenchmarkId::new("base45", len), &text, |b, text| {
+            b.iter(|| base45::decode(black_box(text)).unwrap())
+        });
+        let text = base64::engine::general_purpose::STANDARD.encode(&data);
+        group.bench_with_input(BenchmarkId::new("base64", len), &text, |b, text| {
+            b.iter(|| {
+                base64::engine::general_purpose::STANDARD
+                    .decode(black_box(text))
+                    .unwrap()
+            })
+        });
+        group.bench_with_input(
+            BenchmarkId::new("data-encoding base64", len),
+            &text,
+            |b, text| {
+                b.iter(|| {
+                    data_encoding::BASE64
+                        .decode(black_box(text.as_bytes()))
+                        .unwrap()
+                })
+            },
+        );
+        let text = data_encoding::HEXUPPER.encode(&data);
+        group.bench_with_input(
+            BenchmarkId::new("data-encoding hex", len),
+            &text,
+            |b, text| {
+                b.iter(|| {
+                    data_encoding::HEXUPPER
+                        .decode(black_box(text.as_bytes()))
+                        .unwrap()
+                })
+            },
+        );
+    }
+    group.finish();
+}
+
+criterion_group!(benches, encode, decode);
+criterion_main!(benches);
@@ -0,0 +1,61 @@
+//! Instruction and cache-miss counts for the core loops, measured under Callgrind. Unlike the
+//! wall-clock `throughput` suite these are deterministic, so CI can flag regressions against the
+//! previous run. Needs valgrind and the runner: `cargo install iai-callgrind-runner --version
+//! 0.16.1`, then `cargo bench --bench instructions`.
+
+use std::hint::black_box;
+
+use iai_callgrind::{
+    Callgrind, EventKind, LibraryBenchmarkConfig, library_benchmark, library_benchmark_group, main,
+};
+
+fn payload(len: usize) -> Vec<u8> {
+    (0..len as u32)
+        .map(|i| (i.wrapping_mul(2654435761) >> 13) as u8)
+        .collect()
+}
+
+fn text(len: usize) -> String {
+    qr_base45::encode(payload(len))
+}
+
+/// `text(len)` with its last character replaced by one outside the alphabet.
+fn invalid_text(len: usize) -> String {
+    let mut text = text(len);
+    text.pop();
+    text.push('a');
+    text
+}
+
+#[library_benchmark]
+#[benches::sizes(args = [16, 1024, 65536], setup = payload)]
+fn encode(data: Vec<u8>) -> String {
+    black_box(qr_base45::encode(&data))
+}
+
+#[library_benchmark]
+#[benches::sizes(args = [16, 1024, 65536], setup = text)]
+fn decode(text: String) -> Vec<u8> {
+    black_box(qr_base45::decode(&text).unwrap())
+}
+
+#[library_benchmark]
+#[benches::sizes(args = [16, 1024, 65536], setup = invalid_text)]
+fn decode_invalid(text: String) -> qr_base45::DecodeError {
+    black_box(qr_base45::decode(&text).unwrap_err())
+}
+
+library_benchmark_group!(
+    name = core_loops;
+    benchmarks = encode, decode, decode_invalid
+);
+
+main!(
+    // Instruction counts are exact; cache misses vary slightly with the environment
+    config = LibraryBenchmarkConfig::default().tool(
+        Callgrind::default()
+            .soft_limits([(EventKind::Ir, 1.0), (EventKind::LLhits, 5.0), (EventKind::RamHits, 5.0)])
+            .fail_fast(true)
+    );
+    library_benchmark_groups = core_loops
+);
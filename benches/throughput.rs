@@ -0,0 +1,70 @@
+//! Encode and decode throughput across payload sizes: `cargo bench`.
+
+use std::hint::black_box;
+
+use criterion::{BenchmarkId, Criterion, Throughput, criterion_group, criterion_main};
+
+/// Payload sizes in bytes: a short QR payload up to a large export.
+const SIZES: [usize; 4] = [16, 1024, 64 * 1024, 4 * 1024 * 1024];
+
+fn payload(len: usize) -> Vec<u8> {
+    (0..len as u32)
+        .map(|i| (i.wrapping_mul(2654435761) >> 13) as u8)
+        .collect()
+}
+
+fn label(len: usize) -> String {
+    match len {
+        n if n >= 1 << 20 => format!("{} MiB", n >> 20),
+        n if n >= 1 << 10 => format!("{} KiB", n >> 10),
+        n => format!("{n} B"),
+    }
+}
+
+fn encode(c: &mut Criterion) {
+    let mut group = c.benchmark_group("encode");
+    for len in SIZES {
+        let data = payload(len);
+        let mut out = vec![0; qr_base45::encoded_len(len)];
+        group.throughput(Throughput::Bytes(len as u64));
+        group.bench_with_input(BenchmarkId::new("encode", label(len)), &data, |b, data| {
+            b.iter(|| qr_base45::encode(black_box(data)))
+        });
+        group.bench_with_input(
+            BenchmarkId::new("encode_slice", label(len)),
+            &data,
+            |b, data| b.iter(|| qr_base45::encode_slice(black_box(data), &mut out)),
+        );
+    }
+    group.finish();
+}
+
+fn decode(c: &mut Criterion) {
+    let mut group = c.benchmark_group("decode");
+    for len in SIZES {
+        let text = qr_base45::encode(payload(len));
+        let mut out = vec![0; len];
+        // Throughput counts input characters, so valid and invalid inputs compare directly
+        group.throughput(Throughput::Bytes(text.len() as u64));
+        group.bench_with_input(BenchmarkId::new("decode", label(len)), &text, |b, text| {
+            b.iter(|| qr_base45::decode(black_box(text)))
+        });
+        group.bench_with_input(
+            BenchmarkId::new("decode_slice", label(len)),
+            &text,
+            |b, text| b.iter(|| qr_base45::decode_slice(black_box(text), &mut out)),
+        );
+        // The whole input is scanned before the error in the last group is found
+        let mut invalid = text.clone();
+        invalid.replace_range(text.len() - 1.., "a");
+        group.bench_with_input(
+            BenchmarkId::new("decode_invalid", label(len)),
+            &invalid,
+            |b, text| b.iter(|| qr_base45::decode(black_box(text))),
+        );
+    }
+    group.finish();
+}
+
+criterion_group!(benches, encode, decode);
+criterion_main!(benches);
@@ -0,0 +1,136 @@
+//! ASCII-armored Base45 blocks for pasting long payloads into email and config files.
+//!
+//! ```text
+//! -----BEGIN BASE45-----
+//! <payload, LINE_WIDTH characters per line>
+//! =<CRC-16 of the payload, Base45-encoded>
+//! -----END BASE45-----
+//! ```
+//!
+//! Base45 includes the space character, so lines may begin or end with a space; transports that
+//! strip trailing whitespace will corrupt the block, which the checksum then reports.
+
+use alloc::string::String;
+use alloc::vec::Vec;
+
+use crate::check::crc16;
+use crate::{Base45Error, ErrorKind, decode, encode};
+
+/// First line of an armored block.
+pub const BEGIN: &str = "-----BEGIN BASE45-----";
+/// Last line of an armored block.
+pub const END: &str = "-----END BASE45-----";
+/// Number of payload characters per line.
+pub const LINE_WIDTH: usize = 64;
+
+/// Wrap `input` in an armored block with fixed-width lines and a trailing checksum line.
+pub fn encode_armored<T: AsRef<[u8]>>(input: T) -> String {
+    let input = input.as_ref();
+    let body = encode(input);
+    let mut out =
+        String::with_capacity(body.len() + body.len() / LINE_WIDTH + BEGIN.len() + END.len() + 8);
+    out.push_str(BEGIN);
+    out.push('\n');
+    // Base45 output is ASCII, so splitting at any byte offset stays on a char boundary
+    for line in body.as_bytes().chunks(LINE_WIDTH) {
+        line.iter().for_each(|&b| out.push(b as char));
+        out.push('\n');
+    }
+    out.push('=');
+    out.push_str(&encode(crc16(input).to_be_bytes()));
+    out.push('\n');
+    out.push_str(END);
+    out.push('\n');
+    out
+}
+
+/// Parse an armored block, verify its checksum and return the payload.
+///
+/// Blank lines around the block and `\r\n` line endings are accepted. Returns `InvalidArmor` if the
+/// header, footer or checksum line is missing, and `ChecksumMismatch` if the payload was altered.
+pub fn decode_armored(s: &str) -> Result<Vec<u8>, Base45Error> {
+    let mut lines = s
+        .lines()
+        .map(|l| l.strip_suffix('\r').unwrap_or(l))
+        .skip_while(|l| l.is_empty());
+    if lines.next() != Some(BEGIN) {
+        return Err(Base45Error::new(ErrorKind::InvalidArmor));
+    }
+    let mut body = String::with_capacity(s.len());
+    let mut checksum = None;
+    for line in lines.by_ref() {
+        if let Some(crc) = line.strip_prefix('=') {
+            checksum = Some(crc);
+            break;
+        }
+        body.push_str(line);
+    }
+    let (Some(crc), Some(END)) = (checksum, lines.next()) else {
+        return Err(Base45Error::new(ErrorKind::InvalidArmor));
+    };
+    if lines.any(|l| !l.is_empty()) {
+        return Err(Base45Error::new(ErrorKind::InvalidArmor));
+    }
+    let expected = match decode(crc)?[..] {
+        [hi, lo] => u16::from_be_bytes([hi, lo]),
+        _ => return Err(Base45Error::new(ErrorKind::InvalidArmor)),
+    };
+    let data = decode(&body)?;
+    if crc16(&data) != expected {
+        return Err(Base45Error::new(ErrorKind::ChecksumMismatch));
+    }
+    Ok(data)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn armored_roundtrip() {
+        let data: Vec<u8> = (0..=255).collect();
+        let block = encode_armored(&data);
+        let lines: Vec<&str> = block.lines().collect();
+        assert_eq!(lines[0], BEGIN);
+        assert_eq!(*lines.last().unwrap(), END);
+        assert!(
+            lines[1..lines.len() - 2]
+                .iter()
+                .all(|l| l.len() <= LINE_WIDTH)
+        );
+        assert!(lines[lines.len() - 2].starts_with('='));
+        assert_eq!(decode_armored(&block).unwrap(), data);
+
+        let crlf = format!("\r\n{}\r\n", block.replace('\n', "\r\n"));
+        assert_eq!(decode_armored(&crlf).unwrap(), data);
+        assert_eq!(decode_armored(&encode_armored(b"")).unwrap(), b"");
+    }
+
+    #[test]
+    fn rejects_damaged_blocks() {
+        let block = encode_armored(b"Hello!!");
+        assert_eq!(
+            block,
+            "-----BEGIN BASE45-----\n%69 VD92EX0\n=TGI\n-----END BASE45-----\n"
+        );
+        let tampered = block.replace("%69 VD92EX0", "%69 VD92EX1");
+        assert!(matches!(
+            decode_armored(&tampered),
+            Err(e) if e.kind == ErrorKind::ChecksumMismatch
+        ));
+        let no_crc = block.replace("=TGI\n", "");
+        assert!(matches!(
+            decode_armored(&no_crc),
+            Err(e) if e.kind == ErrorKind::InvalidArmor
+        ));
+        assert!(matches!(
+            decode_armored("%69 VD92EX0"),
+            Err(e) if e.kind == ErrorKind::InvalidArmor
+        ));
+        let trailing = format!("{block}junk\n");
+        assert!(matches!(
+            decode_armored(&trailing),
+            Err(e) if e.kind == ErrorKind::InvalidArmor
+        ));
+    }
+}
@@ -0,0 +1,132 @@
+//! `bytes` crate integration: encoding from any `Buf`, decoding into `Bytes` and `BytesMut`.
+
+use alloc::string::String;
+use alloc::vec;
+
+use bytes::{Buf, Bytes, BytesMut};
+
+use crate::{
+    Base45Error, ascii_string, decode, decode_slice, decoded_len, encode_exact, encode_pair,
+    encode_single, encoded_len,
+};
+
+/// Encode the remaining bytes of `input`, which may be split across several chunks (a `Chain`,
+/// a `VecDeque`), consuming them.
+///
+/// ```
+/// use bytes::Buf;
+///
+/// let input = (&b"iet"[..]).chain(&b"f!"[..]);
+/// assert_eq!(qr_base45::encode_buf(input), "QED8WEX0");
+/// ```
+pub fn encode_buf<B: Buf>(input: B) -> String {
+    let mut out = vec![0; encoded_len(input.remaining())];
+    encode_chunks(input, &mut out);
+    ascii_string(out)
+}
+
+/// Encode the remaining bytes of `input`, consuming them, and append the Base45 ASCII to `dst`.
+/// Returns the number of characters written.
+pub fn encode_to_bytes_mut<B: Buf>(input: B, dst: &mut BytesMut) -> usize {
+    let len = encoded_len(input.remaining());
+    let start = dst.len();
+    dst.resize(start + len, 0);
+    encode_chunks(input, &mut dst[start..]);
+    len
+}
+
+/// Decode `s` into `Bytes`, handing over the decoded allocation without a copy.
+///
+/// ```
+/// assert_eq!(qr_base45::decode_to_bytes("QED8WEX0").unwrap(), &b"ietf!"[..]);
+/// ```
+pub fn decode_to_bytes(s: &str) -> Result<Bytes, Base45Error> {
+    decode(s).map(Bytes::from)
+}
+
+/// Decode `s`, appending the raw bytes to `dst`. Returns the number of bytes appended. On error
+/// `dst` is restored to its original length.
+///
+/// ```
+/// let mut dst = bytes::BytesMut::from(&b">"[..]);
+/// assert_eq!(qr_base45::decode_to_bytes_mut("QED8WEX0", &mut dst), Ok(5));
+/// assert_eq!(dst, &b">ietf!"[..]);
+/// ```
+pub fn decode_to_bytes_mut(s: &str, dst: &mut BytesMut) -> Result<usize, Base45Error> {
+    let start = dst.len();
+    let res = decoded_len(s).and_then(|len| {
+        dst.resize(start + len, 0);
+        decode_slice(s, &mut dst[start..])
+    });
+    dst.truncate(start + *res.as_ref().unwrap_or(&0));
+    res
+}
+
+/// Encode the chunks of `input` into `out`, which must be exactly the encoded length. A group
+/// split between two chunks is encoded from a held byte.
+fn encode_chunks<B: Buf>(mut input: B, out: &mut [u8]) {
+    let mut at = 0;
+    let mut pending = None;
+    while input.has_remaining() {
+        let chunk = input.chunk();
+        let n = chunk.len();
+        let chunk = match (pending.take(), chunk) {
+            (Some(hi), [lo, rest @ ..]) => {
+                out[at..at + 3].copy_from_slice(&encode_pair(u16::from_be_bytes([hi, *lo])));
+                at += 3;
+                rest
+            }
+            _ => chunk,
+        };
+        let whole = chunk.len() / 2 * 2;
+        let len = whole / 2 * 3;
+        encode_exact(&chunk[..whole], &mut out[at..at + len]);
+        at += len;
+        if let [last] = chunk[whole..] {
+            pending = Some(last);
+        }
+        input.advance(n);
+    }
+    if let Some(last) = pending {
+        out[at..].copy_from_slice(&encode_single(last));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ErrorKind;
+    use alloc::vec::Vec;
+
+    #[test]
+    fn chunked_input_matches_encode() {
+        let data: Vec<u8> = (0..301u32).map(|i| (i * 167 + 13) as u8).collect();
+        for split in [0, 1, 2, 3, 150, 301] {
+            let (a, b) = data.split_at(split);
+            // A third chunk of one byte leaves an odd byte held across every boundary
+            let input = a.chain(b).chain(&[0x5A][..]);
+            let mut all = data.clone();
+            all.push(0x5A);
+            assert_eq!(encode_buf(input), crate::encode(&all));
+
+            let mut dst = BytesMut::from(&b"> "[..]);
+            assert_eq!(
+                encode_to_bytes_mut(a.chain(b), &mut dst),
+                encoded_len(data.len())
+            );
+            assert_eq!(dst[2..], *crate::encode(&data).as_bytes());
+        }
+    }
+
+    #[test]
+    fn decode_appends_or_restores() {
+        let mut dst = BytesMut::from(&b"> "[..]);
+        let err = decode_to_bytes_mut("QED8WEX0BB8a", &mut dst).unwrap_err();
+        assert_eq!((err.kind, err.position), (ErrorKind::InvalidChar, 11));
+        let err = decode_to_bytes_mut("QED8WEX", &mut dst).unwrap_err();
+        assert_eq!(err.kind, ErrorKind::Dangling);
+        assert_eq!(dst, &b"> "[..]);
+        assert_eq!(decode_to_bytes_mut("", &mut dst), Ok(0));
+        assert_eq!(decode_to_bytes("BB8").unwrap(), &b"AB"[..]);
+    }
+}
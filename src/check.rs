@@ -0,0 +1,103 @@
+//! Checksummed Base45: a CRC-16 trailer detects scan and typing corruption of short codes.
+
+use alloc::string::String;
+use alloc::vec::Vec;
+
+use crate::{Base45Error, ErrorKind, decode, encode_into, encoded_len};
+
+/// CRC-16/CCITT-FALSE: polynomial 0x1021, initial value 0xFFFF, no reflection, no final XOR.
+pub(crate) const fn crc16(data: &[u8]) -> u16 {
+    let mut crc: u16 = 0xFFFF;
+    let mut i = 0;
+    while i < data.len() {
+        crc ^= (data[i] as u16) << 8;
+        let mut bit = 0;
+        while bit < 8 {
+            crc = if crc & 0x8000 != 0 {
+                (crc << 1) ^ 0x1021
+            } else {
+                crc << 1
+            };
+            bit += 1;
+        }
+        i += 1;
+    }
+    crc
+}
+
+/// Encode `input` followed by its CRC-16/CCITT (big-endian) so corruption can be detected on decode.
+pub fn encode_check<T: AsRef<[u8]>>(input: T) -> String {
+    let input = input.as_ref();
+    let crc = crc16(input).to_be_bytes();
+    let mut out = String::with_capacity(encoded_len(input.len() + 2));
+    // Encode the payload's whole pairs first, then the last odd byte (if any) together with the CRC,
+    // so the output is exactly `encode(input ++ crc)`.
+    let split = input.len() & !1;
+    encode_into(&input[..split], &mut out);
+    match input[split..] {
+        [last] => encode_into([last, crc[0], crc[1]], &mut out),
+        _ => encode_into(crc, &mut out),
+    };
+    out
+}
+
+/// Decode text produced by [`encode_check`], verify the trailing CRC-16 and strip it.
+/// Returns `ChecksumMismatch` if the checksum is wrong or missing.
+pub fn decode_check(s: &str) -> Result<Vec<u8>, Base45Error> {
+    let mut data = decode(s)?;
+    let Some(split) = data.len().checked_sub(2) else {
+        return Err(Base45Error::new(ErrorKind::ChecksumMismatch));
+    };
+    let expected = u16::from_be_bytes([data[split], data[split + 1]]);
+    data.truncate(split);
+    if crc16(&data) != expected {
+        return Err(Base45Error::new(ErrorKind::ChecksumMismatch));
+    }
+    Ok(data)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn crc_check_value() {
+        assert_eq!(crc16(b"123456789"), 0x29B1);
+        assert_eq!(crc16(b""), 0xFFFF);
+    }
+
+    #[test]
+    fn checked_roundtrip() {
+        for case in [&b""[..], b"A", b"AB", b"ietf!", b"Hello!!"] {
+            let s = encode_check(case);
+            let mut raw = case.to_vec();
+            raw.extend_from_slice(&crc16(case).to_be_bytes());
+            assert_eq!(s, crate::encode(&raw));
+            assert_eq!(decode_check(&s).unwrap(), case);
+        }
+    }
+
+    #[test]
+    fn detects_corruption() {
+        let s = encode_check(b"ietf!");
+        // A single mistyped character that still decodes
+        let corrupted = format!("0{}", &s[1..]);
+        assert_ne!(corrupted, s);
+        assert!(matches!(
+            decode_check(&corrupted),
+            Err(e) if e.kind == ErrorKind::ChecksumMismatch
+        ));
+        assert!(matches!(
+            decode_check(&crate::encode(b"ietf!")),
+            Err(e) if e.kind == ErrorKind::ChecksumMismatch
+        ));
+        assert!(matches!(
+            decode_check("00"),
+            Err(e) if e.kind == ErrorKind::ChecksumMismatch
+        ));
+        assert!(matches!(
+            decode_check("BB8A"),
+            Err(e) if e.kind == ErrorKind::Dangling
+        ));
+    }
+}
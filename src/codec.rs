@@ -0,0 +1,67 @@
+//! Encoding-agnostic interface so callers can swap Base45 for another text encoding.
+
+use alloc::string::String;
+use alloc::vec::Vec;
+
+use crate::Base45Error;
+
+/// A binary-to-text encoding.
+///
+/// Implemented by [`Base45`]; applications can implement it for other encodings (base64, hex)
+/// and parameterize their transports over `C: Codec`.
+pub trait Codec {
+    /// Error returned when decoding fails.
+    type Error;
+
+    /// Encode arbitrary bytes into text.
+    fn encode(&self, input: &[u8]) -> String;
+
+    /// Decode text produced by [`Codec::encode`] back into bytes.
+    fn decode(&self, s: &str) -> Result<Vec<u8>, Self::Error>;
+
+    /// Length of the text produced by encoding `n` bytes.
+    fn encoded_len(&self, n: usize) -> usize;
+}
+
+/// The RFC 9285 Base45 encoding as a [`Codec`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct Base45;
+
+impl Codec for Base45 {
+    type Error = Base45Error;
+
+    fn encode(&self, input: &[u8]) -> String {
+        crate::encode(input)
+    }
+
+    fn decode(&self, s: &str) -> Result<Vec<u8>, Base45Error> {
+        crate::decode(s)
+    }
+
+    fn encoded_len(&self, n: usize) -> usize {
+        crate::encoded_len(n)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ErrorKind;
+
+    fn roundtrip<C: Codec>(codec: &C, data: &[u8]) -> Result<Vec<u8>, C::Error> {
+        let s = codec.encode(data);
+        assert_eq!(s.len(), codec.encoded_len(data.len()));
+        codec.decode(&s)
+    }
+
+    #[test]
+    fn base45_codec() {
+        assert_eq!(roundtrip(&Base45, b"Hello!!").unwrap(), b"Hello!!");
+        let dynamic: &dyn Codec<Error = Base45Error> = &Base45;
+        assert_eq!(dynamic.encode(b"AB"), "BB8");
+        assert!(matches!(
+            dynamic.decode("ZZ"),
+            Err(e) if e.kind == ErrorKind::TailOverflow
+        ));
+    }
+}
@@ -0,0 +1,181 @@
+//! Database column support for [`Base45String`]: stored as text, validated when read back.
+
+use crate::Base45String;
+
+#[cfg(feature = "sqlx")]
+mod sqlx_impls {
+    use alloc::string::String;
+
+    use sqlx::encode::IsNull;
+    use sqlx::error::BoxDynError;
+    use sqlx::{Database, Decode, Encode, Type};
+
+    use super::Base45String;
+
+    impl<DB: Database> Type<DB> for Base45String
+    where
+        String: Type<DB>,
+    {
+        fn type_info() -> DB::TypeInfo {
+            <String as Type<DB>>::type_info()
+        }
+
+        fn compatible(ty: &DB::TypeInfo) -> bool {
+            <String as Type<DB>>::compatible(ty)
+        }
+    }
+
+    impl<'q, DB: Database> Encode<'q, DB> for Base45String
+    where
+        String: Encode<'q, DB>,
+    {
+        fn encode(self, buf: &mut DB::ArgumentBuffer<'q>) -> Result<IsNull, BoxDynError> {
+            self.into_string().encode(buf)
+        }
+
+        fn encode_by_ref(&self, buf: &mut DB::ArgumentBuffer<'q>) -> Result<IsNull, BoxDynError> {
+            <String as Encode<'q, DB>>::encode_by_ref(&self.0, buf)
+        }
+
+        fn produces(&self) -> Option<DB::TypeInfo> {
+            <String as Encode<'q, DB>>::produces(&self.0)
+        }
+
+        fn size_hint(&self) -> usize {
+            <String as Encode<'q, DB>>::size_hint(&self.0)
+        }
+    }
+
+    /// Text that is not valid Base45 fails to decode with the [`Base45Error`](crate::Base45Error).
+    impl<'r, DB: Database> Decode<'r, DB> for Base45String
+    where
+        &'r str: Decode<'r, DB>,
+    {
+        fn decode(value: DB::ValueRef<'r>) -> Result<Self, BoxDynError> {
+            Ok(<&str as Decode<'r, DB>>::decode(value)?.parse()?)
+        }
+    }
+}
+
+#[cfg(feature = "diesel")]
+mod diesel_impls {
+    use alloc::string::String;
+
+    use diesel::backend::Backend;
+    use diesel::deserialize::{self, FromSql};
+    use diesel::serialize::{self, Output, ToSql};
+    use diesel::sql_types::Text;
+
+    use super::Base45String;
+
+    impl<DB> ToSql<Text, DB> for Base45String
+    where
+        DB: Backend,
+        str: ToSql<Text, DB>,
+    {
+        fn to_sql<'b>(&'b self, out: &mut Output<'b, '_, DB>) -> serialize::Result {
+            self.as_str().to_sql(out)
+        }
+    }
+
+    /// Text that is not valid Base45 fails to load with the [`Base45Error`](crate::Base45Error).
+    impl<DB> FromSql<Text, DB> for Base45String
+    where
+        DB: Backend,
+        String: FromSql<Text, DB>,
+    {
+        fn from_sql(bytes: DB::RawValue<'_>) -> deserialize::Result<Self> {
+            Ok(Base45String::try_from(String::from_sql(bytes)?)?)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[cfg(feature = "sqlx")]
+    #[test]
+    fn sqlx_round_trip() {
+        use sqlx::{Connection, SqliteConnection};
+
+        let rt = tokio::runtime::Builder::new_current_thread()
+            .build()
+            .unwrap();
+        rt.block_on(async {
+            let mut conn = SqliteConnection::connect("sqlite::memory:").await.unwrap();
+            sqlx::query("CREATE TABLE t (payload TEXT)")
+                .execute(&mut conn)
+                .await
+                .unwrap();
+            let s = Base45String::encode(b"ietf!");
+            sqlx::query("INSERT INTO t VALUES (?)")
+                .bind(&s)
+                .execute(&mut conn)
+                .await
+                .unwrap();
+            let (back,): (Base45String,) = sqlx::query_as("SELECT payload FROM t")
+                .fetch_one(&mut conn)
+                .await
+                .unwrap();
+            assert_eq!(back, s);
+            let (text,): (String,) = sqlx::query_as("SELECT payload FROM t")
+                .fetch_one(&mut conn)
+                .await
+                .unwrap();
+            assert_eq!(text, "QED8WEX0");
+
+            sqlx::query("INSERT INTO t VALUES ('bb8')")
+                .execute(&mut conn)
+                .await
+                .unwrap();
+            let err = sqlx::query_as::<_, (Base45String,)>("SELECT payload FROM t")
+                .fetch_all(&mut conn)
+                .await
+                .unwrap_err();
+            assert!(
+                err.to_string().contains("invalid base45 character 'b'"),
+                "{err}"
+            );
+        });
+    }
+
+    #[cfg(feature = "diesel")]
+    #[test]
+    fn diesel_round_trip() {
+        use diesel::prelude::*;
+        use diesel::sql_types::Text;
+        use diesel::sqlite::SqliteConnection;
+
+        #[derive(QueryableByName, PartialEq, Debug)]
+        struct Row {
+            #[diesel(sql_type = Text)]
+            payload: Base45String,
+        }
+
+        let mut conn = SqliteConnection::establish(":memory:").unwrap();
+        diesel::sql_query("CREATE TABLE t (payload TEXT)")
+            .execute(&mut conn)
+            .unwrap();
+        let s = Base45String::encode(b"ietf!");
+        diesel::sql_query("INSERT INTO t VALUES (?)")
+            .bind::<Text, _>(&s)
+            .execute(&mut conn)
+            .unwrap();
+        let rows: Vec<Row> = diesel::sql_query("SELECT payload FROM t")
+            .load(&mut conn)
+            .unwrap();
+        assert_eq!(rows, [Row { payload: s }]);
+
+        diesel::sql_query("INSERT INTO t VALUES ('bb8')")
+            .execute(&mut conn)
+            .unwrap();
+        let err = diesel::sql_query("SELECT payload FROM t")
+            .load::<Row>(&mut conn)
+            .unwrap_err();
+        assert!(
+            err.to_string().contains("invalid base45 character 'b'"),
+            "{err}"
+        );
+    }
+}
@@ -0,0 +1,132 @@
+//! Bytes given as Base45 text, e.g. on a command line.
+
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::fmt;
+use core::ops::{Deref, DerefMut};
+use core::str::FromStr;
+
+use crate::Base45Error;
+
+/// Raw bytes parsed from Base45 text. `FromStr` decodes, and `Display` encodes again, so it
+/// works as a command-line argument type: with clap, `#[arg] payload: Base45Bytes` is decoded
+/// during parsing and invalid text is rejected with the position of the failure.
+///
+/// ```
+/// use qr_base45::Base45Bytes;
+///
+/// let payload: Base45Bytes = "QED8WEX0".parse().unwrap();
+/// assert_eq!(payload, b"ietf!");
+/// assert_eq!(payload.to_string(), "QED8WEX0");
+///
+/// let err = "QED8WEx0".parse::<Base45Bytes>().unwrap_err();
+/// assert_eq!(err.to_string(), "invalid base45 character 'x' at index 6: QED8WE{x}0");
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Base45Bytes(pub Vec<u8>);
+
+impl Base45Bytes {
+    /// Unwrap into the decoded bytes.
+    pub fn into_inner(self) -> Vec<u8> {
+        self.0
+    }
+
+    /// Encode the bytes back to Base45 text.
+    pub fn encode(&self) -> String {
+        crate::encode(&self.0)
+    }
+}
+
+impl FromStr for Base45Bytes {
+    type Err = Base45Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        crate::decode(s).map(Base45Bytes)
+    }
+}
+
+impl fmt::Display for Base45Bytes {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        crate::encode_fmt(&self.0, f)
+    }
+}
+
+impl Deref for Base45Bytes {
+    type Target = Vec<u8>;
+
+    fn deref(&self) -> &Vec<u8> {
+        &self.0
+    }
+}
+
+impl DerefMut for Base45Bytes {
+    fn deref_mut(&mut self) -> &mut Vec<u8> {
+        &mut self.0
+    }
+}
+
+impl AsRef<[u8]> for Base45Bytes {
+    fn as_ref(&self) -> &[u8] {
+        &self.0
+    }
+}
+
+impl From<Vec<u8>> for Base45Bytes {
+    fn from(bytes: Vec<u8>) -> Self {
+        Base45Bytes(bytes)
+    }
+}
+
+impl From<Base45Bytes> for Vec<u8> {
+    fn from(bytes: Base45Bytes) -> Vec<u8> {
+        bytes.0
+    }
+}
+
+impl<const N: usize> PartialEq<[u8; N]> for Base45Bytes {
+    fn eq(&self, other: &[u8; N]) -> bool {
+        self.0 == other
+    }
+}
+
+impl<const N: usize> PartialEq<&[u8; N]> for Base45Bytes {
+    fn eq(&self, other: &&[u8; N]) -> bool {
+        self.0 == *other
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ErrorKind;
+    use clap::Parser;
+
+    #[derive(Parser, Debug)]
+    struct Cli {
+        #[arg(long)]
+        payload: Base45Bytes,
+    }
+
+    #[test]
+    fn parses_as_a_clap_argument() {
+        let cli = Cli::try_parse_from(["tool", "--payload", "%69 VD92EX0"]).unwrap();
+        assert_eq!(cli.payload, b"Hello!!");
+
+        let err = Cli::try_parse_from(["tool", "--payload", "BB8A"]).unwrap_err();
+        assert_eq!(err.kind(), clap::error::ErrorKind::ValueValidation);
+        let message = err.to_string();
+        assert!(
+            message.contains("'BB8A' for '--payload <PAYLOAD>'"),
+            "{message}"
+        );
+        assert!(message.contains("dangling"), "{message}");
+    }
+
+    #[test]
+    fn errors_name_the_failure() {
+        let err = "BB8:::".parse::<Base45Bytes>().unwrap_err();
+        assert_eq!((err.kind, err.position), (ErrorKind::Overflow, 3));
+        assert_eq!(Base45Bytes::from(b"AB".to_vec()).encode(), "BB8");
+        assert_eq!("".parse::<Base45Bytes>().unwrap(), Base45Bytes::default());
+    }
+}
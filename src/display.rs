@@ -0,0 +1,133 @@
+//! Encoding into `core::fmt` sinks: the lazy `Display` adapter and `encode_fmt`.
+
+use core::fmt::{self, Write};
+
+use crate::encode_slice;
+
+/// Base45 characters encoded per call to the sink in [`encode_with`] and [`encode_fmt`].
+const CHUNK_CHARS: usize = 64;
+
+/// Formats the wrapped bytes as Base45 without building an intermediate `String`.
+/// Created by [`display`].
+#[derive(Debug, Clone, Copy)]
+pub struct Base45Display<'a>(&'a [u8]);
+
+/// Wrap bytes so that `{}` formatting writes their Base45 encoding straight into the formatter.
+///
+/// Width, fill and precision flags are ignored.
+pub fn display(bytes: &[u8]) -> Base45Display<'_> {
+    Base45Display(bytes)
+}
+
+impl fmt::Display for Base45Display<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        encode_fmt(self.0, f)
+    }
+}
+
+/// Writes the encoding through [`encode_with`], so `core::fmt` is not involved.
+#[cfg(feature = "ufmt")]
+impl ufmt::uDisplay for Base45Display<'_> {
+    fn fmt<W: ufmt::uWrite + ?Sized>(
+        &self,
+        f: &mut ufmt::Formatter<'_, W>,
+    ) -> Result<(), W::Error> {
+        encode_with(self.0, |s| f.write_str(s))
+    }
+}
+
+/// Encode `input` into any `fmt::Write` sink (`String`, formatters, fixed-capacity strings).
+/// Only write errors from the sink are returned; encoding itself cannot fail.
+///
+/// The output is written in runs of up to 64 characters from a stack buffer, so large inputs
+/// cost one `write_str` per run rather than one `write_char` per character.
+///
+/// Fixed-capacity strings such as `heapless::String<N>` or `arrayvec::ArrayString<N>` report a
+/// full buffer as `fmt::Error` after keeping the runs that fit; check
+/// `encoded_len(input.len()) <= N` first to reject the input before anything is written, or use
+/// `encode_to_heapless` or `encode_to_array_string` with the `heapless` or `arrayvec` feature.
+pub fn encode_fmt<W: Write + ?Sized>(input: &[u8], w: &mut W) -> fmt::Result {
+    encode_with(input, |s| w.write_str(s))
+}
+
+/// Encode `input` through a stack buffer, handing each run of up to 64 characters to `sink`.
+///
+/// Works with any writer that takes `&str`, without `core::fmt`: on a `ufmt::uWrite`,
+/// `encode_with(bytes, |s| w.write_str(s))`, which is what the `ufmt` feature's `uDisplay` for
+/// [`Base45Display`] does. Stops at the first error from `sink` and returns it.
+pub fn encode_with<E>(input: &[u8], mut sink: impl FnMut(&str) -> Result<(), E>) -> Result<(), E> {
+    let mut buf = [0u8; CHUNK_CHARS];
+    // 2 input bytes per 3 characters, so a full buffer takes an even number of bytes
+    for chunk in input.chunks(CHUNK_CHARS / 3 * 2) {
+        let n = encode_slice(chunk, &mut buf).expect("a chunk always fits the buffer");
+        sink(core::str::from_utf8(&buf[..n]).expect("Base45 output is ASCII"))?;
+    }
+    Ok(())
+}
+
+#[cfg(all(test, feature = "alloc"))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn matches_encode() {
+        for case in [&b""[..], b"A", b"AB", b"ietf!", b"Hello!!"] {
+            assert_eq!(display(case).to_string(), crate::encode(case));
+        }
+        assert_eq!(format!("sig={}", display(b"ietf!")), "sig=QED8WEX0");
+    }
+
+    #[test]
+    fn encode_fmt_propagates_sink_errors() {
+        let mut s = String::from("HC1:");
+        encode_fmt(b"ietf!", &mut s).unwrap();
+        assert_eq!(s, "HC1:QED8WEX0");
+
+        // A sink that rejects writes after a fixed capacity
+        struct Capped(usize);
+        impl Write for Capped {
+            fn write_str(&mut self, s: &str) -> fmt::Result {
+                self.0 = self.0.checked_sub(s.len()).ok_or(fmt::Error)?;
+                Ok(())
+            }
+        }
+        assert!(encode_fmt(b"ietf!", &mut Capped(8)).is_ok());
+        assert!(encode_fmt(b"ietf!", &mut Capped(7)).is_err());
+        let data: Vec<u8> = (0..=255).collect();
+        let mut s = String::new();
+        encode_fmt(&data, &mut s).unwrap();
+        assert_eq!(s, crate::encode(&data));
+    }
+
+    #[test]
+    fn encode_with_hands_out_chunks() {
+        let data: Vec<u8> = (0..=255).collect();
+        let mut chunks = Vec::new();
+        encode_with(&data, |s| {
+            chunks.push(s.to_owned());
+            Ok::<_, ()>(())
+        })
+        .unwrap();
+        assert!(chunks.iter().all(|c| c.len() <= CHUNK_CHARS));
+        assert_eq!(chunks.concat(), crate::encode(&data));
+
+        let mut calls = 0;
+        assert_eq!(
+            encode_with(&data, |_| {
+                calls += 1;
+                Err("full")
+            }),
+            Err("full")
+        );
+        assert_eq!(calls, 1);
+    }
+
+    #[cfg(feature = "ufmt")]
+    #[test]
+    fn udisplay_matches_encode() {
+        let data: Vec<u8> = (0..200u32).map(|i| (i * 167 + 13) as u8).collect();
+        let mut out = String::new();
+        ufmt::uwrite!(out, "sig={}", display(&data)).unwrap();
+        assert_eq!(out, format!("sig={}", crate::encode(&data)));
+    }
+}
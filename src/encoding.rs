@@ -0,0 +1,267 @@
+//! Base45 over caller-supplied alphabets, for legacy systems using a non-RFC character set.
+
+#[cfg(feature = "alloc")]
+use alloc::string::String;
+#[cfg(feature = "alloc")]
+use alloc::vec::Vec;
+
+use crate::{BASE45_ALPHABET, Base45Error, ErrorKind, INVALID_DIGIT as INVALID};
+#[cfg(feature = "alloc")]
+use crate::{Codec, char_err, encoded_len, pair_digits, pair_value, single_digits, single_value};
+
+/// Order in which the digits of each group are written.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum DigitOrder {
+    /// Least-significant digit first, as required by RFC 9285.
+    #[default]
+    LsdFirst,
+    /// Most-significant digit first. **Not RFC 9285**: only for interoperating with
+    /// implementations that emit this dialect; its output is not readable by conforming decoders.
+    MsdFirst,
+}
+
+/// A Base45 encoding with its own 45-character alphabet.
+///
+/// Group math and error rules are the same as RFC 9285; only the digit-to-character mapping
+/// changes. [`Encoding::RFC9285`] is the standard alphabet.
+///
+/// ```
+/// use qr_base45::Encoding;
+///
+/// // RFC alphabet with the space replaced by `_`
+/// let mut alphabet = *qr_base45::BASE45_ALPHABET;
+/// alphabet[36] = b'_';
+/// let enc = Encoding::new(alphabet).unwrap();
+/// assert_eq!(enc.encode(b"Hello!!"), "%69_VD92EX0");
+/// assert_eq!(enc.decode("%69_VD92EX0").unwrap(), b"Hello!!");
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Encoding {
+    alphabet: [u8; 45],
+    reverse: [u8; 256],
+    order: DigitOrder,
+}
+
+impl Encoding {
+    /// The RFC 9285 alphabet.
+    pub const RFC9285: Encoding = match Encoding::new(*BASE45_ALPHABET) {
+        Ok(enc) => enc,
+        Err(_) => panic!("RFC 9285 alphabet is valid"),
+    };
+
+    /// Build an encoding from 45 distinct ASCII characters, digit value 0 first.
+    /// Returns `InvalidAlphabet` if a character repeats or is not ASCII.
+    pub const fn new(alphabet: [u8; 45]) -> Result<Encoding, Base45Error> {
+        let mut reverse = [INVALID; 256];
+        let mut i = 0;
+        while i < 45 {
+            let ch = alphabet[i];
+            if !ch.is_ascii() || reverse[ch as usize] != INVALID {
+                return Err(Base45Error::new(ErrorKind::InvalidAlphabet));
+            }
+            reverse[ch as usize] = i as u8;
+            i += 1;
+        }
+        Ok(Encoding {
+            alphabet,
+            reverse,
+            order: DigitOrder::LsdFirst,
+        })
+    }
+
+    /// Use `order` for the digits within each group. Anything but the default
+    /// [`DigitOrder::LsdFirst`] produces non-RFC 9285 output.
+    ///
+    /// ```
+    /// use qr_base45::{DigitOrder, Encoding};
+    ///
+    /// let msd = Encoding::RFC9285.with_digit_order(DigitOrder::MsdFirst);
+    /// assert_eq!(msd.encode(b"AB"), "8BB"); // RFC 9285: "BB8"
+    /// ```
+    pub const fn with_digit_order(mut self, order: DigitOrder) -> Encoding {
+        self.order = order;
+        self
+    }
+
+    /// The digit order used by this encoding.
+    pub const fn digit_order(&self) -> DigitOrder {
+        self.order
+    }
+
+    /// The 45 characters of this alphabet, digit value 0 first.
+    pub const fn alphabet(&self) -> &[u8; 45] {
+        &self.alphabet
+    }
+
+    /// Encode arbitrary bytes with this alphabet.
+    #[cfg(feature = "alloc")]
+    pub fn encode<T: AsRef<[u8]>>(&self, input: T) -> String {
+        let input = input.as_ref();
+        let mut out = String::with_capacity(encoded_len(input.len()));
+        let mut pairs = input.chunks_exact(2);
+        for pair in &mut pairs {
+            let mut digits = pair_digits((pair[0] as u16) * 256 + (pair[1] as u16));
+            if self.order == DigitOrder::MsdFirst {
+                digits.reverse();
+            }
+            for d in digits {
+                out.push(self.alphabet[d as usize] as char);
+            }
+        }
+        if let [last] = *pairs.remainder() {
+            let mut digits = single_digits(last);
+            if self.order == DigitOrder::MsdFirst {
+                digits.reverse();
+            }
+            for d in digits {
+                out.push(self.alphabet[d as usize] as char);
+            }
+        }
+        out
+    }
+
+    /// Decode text written with this alphabet.
+    #[cfg(feature = "alloc")]
+    pub fn decode(&self, s: &str) -> Result<Vec<u8>, Base45Error> {
+        self.decode_groups(s.as_bytes())
+            .map_err(char_err(s.as_bytes()))
+    }
+
+    #[cfg(feature = "alloc")]
+    fn decode_groups(&self, bytes: &[u8]) -> Result<Vec<u8>, Base45Error> {
+        let mut out = Vec::with_capacity(bytes.len() / 3 * 2 + 1);
+        let mut groups = bytes.chunks_exact(3);
+        let mut at = 0;
+        for group in &mut groups {
+            let mut digits = [
+                self.digit(group, 0, at)?,
+                self.digit(group, 1, at)?,
+                self.digit(group, 2, at)?,
+            ];
+            if self.order == DigitOrder::MsdFirst {
+                digits.reverse();
+            }
+            let [c0, c1, c2] = digits;
+            out.extend_from_slice(&pair_value(c0, c1, c2, at)?);
+            at += 3;
+        }
+        let tail = groups.remainder();
+        match tail.len() {
+            2 => {
+                let mut digits = [self.digit(tail, 0, at)?, self.digit(tail, 1, at)?];
+                if self.order == DigitOrder::MsdFirst {
+                    digits.reverse();
+                }
+                out.push(single_value(digits[0], digits[1], at)?);
+            }
+            1 => {
+                self.digit(tail, 0, at)?;
+                return Err(Base45Error::dangling(at));
+            }
+            _ => {}
+        }
+        Ok(out)
+    }
+
+    #[cfg(feature = "alloc")]
+    #[inline]
+    fn digit(&self, group: &[u8], k: usize, at: usize) -> Result<u32, Base45Error> {
+        let byte = group[k];
+        match self.reverse[byte as usize] {
+            INVALID => Err(Base45Error::invalid_byte(byte, at + k)),
+            d => Ok(d as u32),
+        }
+    }
+}
+
+impl Default for Encoding {
+    fn default() -> Self {
+        Encoding::RFC9285
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl Codec for Encoding {
+    type Error = Base45Error;
+
+    fn encode(&self, input: &[u8]) -> String {
+        Encoding::encode(self, input)
+    }
+
+    fn decode(&self, s: &str) -> Result<Vec<u8>, Base45Error> {
+        Encoding::decode(self, s)
+    }
+
+    fn encoded_len(&self, n: usize) -> usize {
+        encoded_len(n)
+    }
+}
+
+#[cfg(all(test, feature = "alloc"))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rfc_alphabet_matches_free_functions() {
+        let enc = Encoding::RFC9285;
+        for case in [&b""[..], b"A", b"AB", b"ietf!", b"Hello!!", &[0xFF; 9]] {
+            let s = enc.encode(case);
+            assert_eq!(s, crate::encode(case));
+            assert_eq!(enc.decode(&s).unwrap(), case);
+        }
+        for bad in ["A", "ZZ", ":::", "bb8", "😀"] {
+            assert_eq!(
+                enc.decode(bad).unwrap_err().to_string(),
+                crate::decode(bad).unwrap_err().to_string(),
+                "{bad:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn msd_first_dialect() {
+        let msd = Encoding::RFC9285.with_digit_order(DigitOrder::MsdFirst);
+        assert_eq!(msd.digit_order(), DigitOrder::MsdFirst);
+        assert_eq!(msd.encode(b"ietf!"), "DEQEW80X");
+        for case in [&b""[..], b"A", b"AB", b"Hello!!", &[0xFF; 9]] {
+            assert_eq!(msd.decode(&msd.encode(case)).unwrap(), case);
+        }
+        // Group limits are the same, with the digits mirrored
+        assert_eq!(msd.decode("WGF").unwrap(), [0xFF, 0xFF]);
+        assert!(matches!(
+            msd.decode("WGG"),
+            Err(e) if e.kind == ErrorKind::Overflow
+        ));
+        assert_eq!(msd.decode("5U").unwrap(), [0xFF]);
+        assert!(matches!(
+            msd.decode("5V"),
+            Err(e) if e.kind == ErrorKind::TailOverflow
+        ));
+    }
+
+    #[test]
+    fn custom_alphabets() {
+        let mut reversed = *BASE45_ALPHABET;
+        reversed.reverse();
+        let enc = Encoding::new(reversed).unwrap();
+        assert_eq!(enc.encode([0, 0]), ":::");
+        assert_eq!(enc.decode(":::").unwrap(), [0, 0]);
+        assert!(matches!(
+            enc.decode("000"),
+            Err(e) if e.kind == ErrorKind::Overflow
+        ));
+
+        let mut dup = *BASE45_ALPHABET;
+        dup[44] = b'0';
+        assert!(matches!(
+            Encoding::new(dup),
+            Err(e) if e.kind == ErrorKind::InvalidAlphabet
+        ));
+        let mut non_ascii = *BASE45_ALPHABET;
+        non_ascii[0] = 0xC3;
+        assert!(matches!(
+            Encoding::new(non_ascii),
+            Err(e) if e.kind == ErrorKind::InvalidAlphabet
+        ));
+    }
+}
@@ -0,0 +1,545 @@
+//! The error type shared by every fallible operation in the crate.
+
+use core::fmt::{self, Write as _};
+use core::ops::Range;
+
+/// What went wrong. The details of where and why live on [`DecodeError`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum ErrorKind {
+    /// A character outside the Base45 alphabet, in `&str` input.
+    InvalidChar,
+    /// A byte outside the Base45 alphabet, in byte-slice input.
+    InvalidByte,
+    /// A single character left over after the last full group.
+    Dangling,
+    /// A 3-character group decoding to more than 65535.
+    Overflow,
+    /// The final 2-character group decoding to more than 255.
+    TailOverflow,
+    /// The output buffer cannot hold the result.
+    BufferTooSmall,
+    /// The output would exceed a configured maximum length.
+    TooLarge,
+    /// A required prefix is missing.
+    MissingPrefix,
+    /// The input is not the exact length the decoder requires.
+    InvalidLength,
+    /// An alphabet that is not 45 distinct ASCII characters.
+    InvalidAlphabet,
+    /// A checksum did not match the data.
+    ChecksumMismatch,
+    /// A malformed armor block.
+    InvalidArmor,
+    /// A malformed, conflicting or missing transport frame.
+    InvalidFrame,
+}
+
+/// Bytes of input kept on each side of the failing character or group for [`Display`](fmt::Display).
+const CONTEXT: usize = 8;
+
+/// Error produced by this crate: an [`ErrorKind`] plus the context needed to act on it.
+///
+/// New fields may be added in minor releases.
+///
+/// ```
+/// use qr_base45::{DecodeError, ErrorKind};
+///
+/// let DecodeError { kind, position, value, .. } = qr_base45::decode("BB8:::").unwrap_err();
+/// assert_eq!(kind, ErrorKind::Overflow);
+/// assert_eq!(position, 3);
+/// assert_eq!(value, Some(91124));
+/// ```
+///
+/// Errors from the `&str` decoders also keep a short excerpt of the input around the failure and
+/// print it after the message, with the offending part in braces. The excerpt is only for
+/// display; it is not compared by `==`.
+///
+/// ```
+/// let err = qr_base45::decode("QED8WEX0BB8~QED8WEX0BB8").unwrap_err();
+/// assert_eq!(
+///     err.to_string(),
+///     "invalid base45 character '~' at index 11: ...8WEX0BB8{~}QED8WEX0..."
+/// );
+/// ```
+#[derive(Debug, Clone, Copy)]
+#[non_exhaustive]
+pub struct DecodeError {
+    /// What went wrong.
+    pub kind: ErrorKind,
+    /// Byte offset into the input of the failing character or group; `0` for errors that are not
+    /// tied to a place in the input.
+    pub position: usize,
+    /// Length in bytes of the failing character or group at `position`; `0` for errors that are
+    /// not tied to a place in the input.
+    pub len: usize,
+    /// What was found: the offending character (as its scalar value) or byte, the decoded value
+    /// of an overflowing group, or the input length that was rejected.
+    pub value: Option<u64>,
+    /// What was allowed instead: the maximum value of an overflowing group, or the required
+    /// input length.
+    pub expected: Option<u64>,
+    context: Context,
+}
+
+/// The name used for [`DecodeError`] throughout the API.
+pub type Base45Error = DecodeError;
+
+impl DecodeError {
+    /// An error of `kind` with no further context.
+    pub const fn new(kind: ErrorKind) -> DecodeError {
+        DecodeError {
+            kind,
+            position: 0,
+            len: 0,
+            value: None,
+            expected: None,
+            context: Context::EMPTY,
+        }
+    }
+
+    pub(crate) const fn invalid_char(ch: char, index: usize) -> DecodeError {
+        DecodeError {
+            position: index,
+            len: ch.len_utf8(),
+            value: Some(ch as u64),
+            ..DecodeError::new(ErrorKind::InvalidChar)
+        }
+    }
+
+    pub(crate) const fn invalid_byte(byte: u8, index: usize) -> DecodeError {
+        DecodeError {
+            position: index,
+            len: 1,
+            value: Some(byte as u64),
+            ..DecodeError::new(ErrorKind::InvalidByte)
+        }
+    }
+
+    pub(crate) const fn dangling(index: usize) -> DecodeError {
+        DecodeError {
+            position: index,
+            len: 1,
+            ..DecodeError::new(ErrorKind::Dangling)
+        }
+    }
+
+    pub(crate) const fn overflow(index: usize, value: u32) -> DecodeError {
+        DecodeError {
+            position: index,
+            len: 3,
+            value: Some(value as u64),
+            expected: Some(65535),
+            ..DecodeError::new(ErrorKind::Overflow)
+        }
+    }
+
+    pub(crate) const fn tail_overflow(index: usize, value: u32) -> DecodeError {
+        DecodeError {
+            position: index,
+            len: 2,
+            value: Some(value as u64),
+            expected: Some(255),
+            ..DecodeError::new(ErrorKind::TailOverflow)
+        }
+    }
+
+    pub(crate) const fn invalid_length(expected: usize, found: usize) -> DecodeError {
+        DecodeError {
+            value: Some(found as u64),
+            expected: Some(expected as u64),
+            ..DecodeError::new(ErrorKind::InvalidLength)
+        }
+    }
+
+    /// The offending character of an `InvalidChar` error.
+    pub fn ch(&self) -> Option<char> {
+        match (self.kind, self.value) {
+            (ErrorKind::InvalidChar, Some(v)) => char::from_u32(v as u32),
+            _ => None,
+        }
+    }
+
+    /// The offending byte of an `InvalidByte` error.
+    pub fn byte(&self) -> Option<u8> {
+        match (self.kind, self.value) {
+            (ErrorKind::InvalidByte, Some(v)) => Some(v as u8),
+            _ => None,
+        }
+    }
+
+    /// Byte range of the input that caused the error, for pointing a diagnostic label at it.
+    /// `None` for errors that are not tied to a place in the input.
+    ///
+    /// ```
+    /// let err = qr_base45::decode("BB8Q~D").unwrap_err();
+    /// assert_eq!(err.span(), Some(4..5));
+    /// ```
+    pub fn span(&self) -> Option<Range<usize>> {
+        (self.len > 0).then(|| self.position..self.position + self.len)
+    }
+
+    /// Remember the input around the failure. `src` is the input starting at byte offset `base`.
+    #[inline]
+    pub(crate) fn with_context(mut self, src: &[u8], base: usize) -> DecodeError {
+        if let Some(span) = self.span() {
+            if let (Some(start), Some(end)) =
+                (span.start.checked_sub(base), span.end.checked_sub(base))
+            {
+                self.context = Context::new(src, start, end);
+                // Input before `src` is gone but still existed
+                self.context.clipped.0 |= base > 0;
+            }
+        }
+        self
+    }
+}
+
+impl PartialEq for DecodeError {
+    fn eq(&self, other: &DecodeError) -> bool {
+        (
+            self.kind,
+            self.position,
+            self.len,
+            self.value,
+            self.expected,
+        ) == (
+            other.kind,
+            other.position,
+            other.len,
+            other.value,
+            other.expected,
+        )
+    }
+}
+
+impl Eq for DecodeError {}
+
+impl ErrorKind {
+    /// The variant name as a static string, e.g. `"InvalidChar"`, for loggers that want a plain
+    /// `&'static str` instead of going through `core::fmt`.
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            ErrorKind::InvalidChar => "InvalidChar",
+            ErrorKind::InvalidByte => "InvalidByte",
+            ErrorKind::Dangling => "Dangling",
+            ErrorKind::Overflow => "Overflow",
+            ErrorKind::TailOverflow => "TailOverflow",
+            ErrorKind::BufferTooSmall => "BufferTooSmall",
+            ErrorKind::TooLarge => "TooLarge",
+            ErrorKind::MissingPrefix => "MissingPrefix",
+            ErrorKind::InvalidLength => "InvalidLength",
+            ErrorKind::InvalidAlphabet => "InvalidAlphabet",
+            ErrorKind::ChecksumMismatch => "ChecksumMismatch",
+            ErrorKind::InvalidArmor => "InvalidArmor",
+            ErrorKind::InvalidFrame => "InvalidFrame",
+        }
+    }
+
+    /// Whether this is either kind of group overflow.
+    pub const fn is_overflow(self) -> bool {
+        matches!(self, ErrorKind::Overflow | ErrorKind::TailOverflow)
+    }
+}
+
+impl From<ErrorKind> for DecodeError {
+    fn from(kind: ErrorKind) -> DecodeError {
+        DecodeError::new(kind)
+    }
+}
+
+impl fmt::Display for DecodeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let at = self.position;
+        let value = self.value.unwrap_or_default();
+        match self.kind {
+            ErrorKind::InvalidChar => match self.ch() {
+                Some(ch) => write!(f, "invalid base45 character {ch:?} at index {at}"),
+                None => write!(f, "invalid base45 character at index {at}"),
+            },
+            ErrorKind::InvalidByte => write!(f, "invalid base45 byte 0x{value:02X} at index {at}"),
+            ErrorKind::Dangling => write!(f, "dangling character at index {at}"),
+            ErrorKind::Overflow => write!(
+                f,
+                "3-character group at index {at} decodes to {value}, above 65535"
+            ),
+            ErrorKind::TailOverflow => write!(
+                f,
+                "final 2-character group at index {at} decodes to {value}, above 255"
+            ),
+            ErrorKind::BufferTooSmall => f.write_str("output buffer too small"),
+            ErrorKind::TooLarge => f.write_str("output would exceed the configured maximum length"),
+            ErrorKind::MissingPrefix => f.write_str("expected prefix is missing"),
+            ErrorKind::InvalidLength => write!(
+                f,
+                "expected {} characters, found {value}",
+                self.expected.unwrap_or_default()
+            ),
+            ErrorKind::InvalidAlphabet => {
+                f.write_str("alphabet must be 45 distinct ASCII characters")
+            }
+            ErrorKind::ChecksumMismatch => f.write_str("checksum mismatch"),
+            ErrorKind::InvalidArmor => f.write_str("malformed armor block"),
+            ErrorKind::InvalidFrame => f.write_str("malformed or missing frame"),
+        }?;
+        if self.context.len > 0 {
+            write!(f, ": {}", self.context)?;
+        }
+        Ok(())
+    }
+}
+
+// `std::error::Error` is a re-export of this trait, so one impl serves both `std` and `no_std`
+impl core::error::Error for DecodeError {}
+
+/// The kind name from [`ErrorKind::as_str`] and the position, e.g. `InvalidChar at 11`.
+#[cfg(feature = "defmt")]
+impl defmt::Format for DecodeError {
+    fn format(&self, f: defmt::Formatter<'_>) {
+        defmt::write!(f, "{=str} at {=usize}", self.kind.as_str(), self.position);
+    }
+}
+
+#[cfg(feature = "defmt")]
+impl defmt::Format for ErrorKind {
+    fn format(&self, f: defmt::Formatter<'_>) {
+        defmt::write!(f, "{=str}", self.as_str());
+    }
+}
+
+/// The kind name from [`ErrorKind::as_str`] and the position, e.g. `InvalidChar at 11`.
+#[cfg(feature = "ufmt")]
+impl ufmt::uDisplay for DecodeError {
+    fn fmt<W: ufmt::uWrite + ?Sized>(
+        &self,
+        f: &mut ufmt::Formatter<'_, W>,
+    ) -> Result<(), W::Error> {
+        ufmt::uwrite!(f, "{} at {}", self.kind.as_str(), self.position)
+    }
+}
+
+#[cfg(feature = "ufmt")]
+impl ufmt::uDisplay for ErrorKind {
+    fn fmt<W: ufmt::uWrite + ?Sized>(
+        &self,
+        f: &mut ufmt::Formatter<'_, W>,
+    ) -> Result<(), W::Error> {
+        f.write_str(self.as_str())
+    }
+}
+
+/// A `qr_base45::<kind>` code and a label on [`span`](DecodeError::span). The error does not keep
+/// the input, so attach it to the report for the label to be drawn over it:
+///
+/// ```
+/// let input = "BB8Q~D";
+/// let err = qr_base45::decode(input).unwrap_err();
+/// let report = miette::Report::new(err).with_source_code(input);
+/// # let _ = report;
+/// ```
+#[cfg(feature = "miette")]
+impl miette::Diagnostic for DecodeError {
+    fn code<'a>(&'a self) -> Option<alloc::boxed::Box<dyn fmt::Display + 'a>> {
+        let code = alloc::format!("qr_base45::{}", self.kind.as_str());
+        Some(alloc::boxed::Box::new(code))
+    }
+
+    fn labels(&self) -> Option<alloc::boxed::Box<dyn Iterator<Item = miette::LabeledSpan> + '_>> {
+        let label = match self.kind {
+            ErrorKind::InvalidChar | ErrorKind::InvalidByte => "not in the Base45 alphabet",
+            ErrorKind::Dangling => "a lone character cannot encode a byte",
+            ErrorKind::Overflow => "group above 65535",
+            ErrorKind::TailOverflow => "final group above 255",
+            _ => "here",
+        };
+        let span = self.span()?;
+        let label = miette::LabeledSpan::new_with_span(Some(label.into()), span);
+        Some(alloc::boxed::Box::new(core::iter::once(label)))
+    }
+}
+
+/// Excerpt of the input around a failure, kept inline so errors stay `Copy` and allocation-free.
+#[derive(Clone, Copy)]
+struct Context {
+    buf: [u8; 2 * CONTEXT + 4],
+    len: u8,
+    /// Range of `buf` holding the failing character or group.
+    mark: (u8, u8),
+    /// Whether input was cut off before and after the excerpt.
+    clipped: (bool, bool),
+}
+
+impl Context {
+    const EMPTY: Context = Context {
+        buf: [0; 2 * CONTEXT + 4],
+        len: 0,
+        mark: (0, 0),
+        clipped: (false, false),
+    };
+
+    /// Excerpt of `src` around `start..end`, widened by up to [`CONTEXT`] bytes on each side
+    /// without splitting a UTF-8 character.
+    #[inline]
+    fn new(src: &[u8], start: usize, end: usize) -> Context {
+        let end = end.min(src.len());
+        if start >= end || end - start > 4 {
+            return Context::EMPTY;
+        }
+        let continuation = |i: usize| src.get(i).is_some_and(|&b| b & 0xC0 == 0x80);
+        let mut lo = start.saturating_sub(CONTEXT);
+        while lo < start && continuation(lo) {
+            lo += 1;
+        }
+        let mut hi = (end + CONTEXT).min(src.len());
+        while hi > end && continuation(hi) {
+            hi -= 1;
+        }
+        let mut ctx = Context::EMPTY;
+        let (Some(dst), Some(excerpt)) = (ctx.buf.get_mut(..hi - lo), src.get(lo..hi)) else {
+            return Context::EMPTY;
+        };
+        dst.copy_from_slice(excerpt);
+        ctx.len = (hi - lo) as u8;
+        ctx.mark = ((start - lo) as u8, (end - lo) as u8);
+        ctx.clipped = (lo > 0, hi < src.len());
+        ctx
+    }
+}
+
+/// Write `bytes` on one line: control characters escaped, invalid UTF-8 as `\xNN`.
+fn write_escaped(f: &mut fmt::Formatter<'_>, bytes: &[u8]) -> fmt::Result {
+    for chunk in bytes.utf8_chunks() {
+        for ch in chunk.valid().chars() {
+            if ch.is_control() {
+                write!(f, "{}", ch.escape_default())?;
+            } else {
+                f.write_char(ch)?;
+            }
+        }
+        for b in chunk.invalid() {
+            write!(f, "\\x{b:02X}")?;
+        }
+    }
+    Ok(())
+}
+
+impl fmt::Display for Context {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let (a, b) = (self.mark.0 as usize, self.mark.1 as usize);
+        if self.clipped.0 {
+            f.write_str("...")?;
+        }
+        write_escaped(f, &self.buf[..a])?;
+        f.write_char('{')?;
+        write_escaped(f, &self.buf[a..b])?;
+        f.write_char('}')?;
+        write_escaped(f, &self.buf[b..self.len as usize])?;
+        if self.clipped.1 {
+            f.write_str("...")?;
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Debug for Context {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.len == 0 {
+            f.write_str("None")
+        } else {
+            write!(f, "\"{self}\"")
+        }
+    }
+}
+
+#[cfg(all(test, feature = "alloc"))]
+mod tests {
+    use super::ErrorKind;
+    use crate::{decode, decode_in_place, decode_lenient};
+
+    #[test]
+    fn overflow_kinds_are_distinct() {
+        let triple = decode("BB8:::").unwrap_err();
+        let tail = decode("BB8ZZ").unwrap_err();
+        assert_eq!(triple.kind, ErrorKind::Overflow);
+        assert_eq!(tail.kind, ErrorKind::TailOverflow);
+        assert!(triple.kind.is_overflow() && tail.kind.is_overflow());
+        assert!(!ErrorKind::Dangling.is_overflow());
+        assert_eq!(tail.kind.as_str(), format!("{:?}", tail.kind));
+        // The same digits that overflow a tail are in range as part of a full group
+        assert_eq!(decode("ZZ0").unwrap(), [6, 74]);
+        assert_eq!(decode(":::BB8").unwrap_err().kind, ErrorKind::Overflow);
+    }
+
+    #[test]
+    fn display_shows_input_excerpt() {
+        assert_eq!(
+            decode("BB8Q~D").unwrap_err().to_string(),
+            "invalid base45 character '~' at index 4: BB8Q{~}D"
+        );
+        assert_eq!(
+            decode("BB8:::").unwrap_err().to_string(),
+            "3-character group at index 3 decodes to 91124, above 65535: BB8{:::}"
+        );
+        // Clipped at char boundaries, with line breaks escaped to keep log lines whole
+        let err = decode_lenient("ééééé\nBB8QED😀").unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "invalid base45 character 'é' at index 0: {é}éééé..."
+        );
+        let err = decode("QED8WEX0\t").unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "invalid base45 character '\\t' at index 8: QED8WEX0{\\t}"
+        );
+        // In-place decoding has overwritten the start of the input; only the intact tail is shown
+        let err = decode_in_place("BB8".repeat(3) + "~").unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "invalid base45 character '~' at index 9: ...BB8{~}"
+        );
+        let err = decode_in_place("BB8".repeat(9) + "~").unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "invalid base45 character '~' at index 27: ...B8BB8BB8{~}"
+        );
+        assert_eq!(
+            decode("BB8ZZ").unwrap_err().to_string(),
+            "final 2-character group at index 3 decodes to 1610, above 255: BB8{ZZ}"
+        );
+        // Errors without a position carry no excerpt
+        assert_eq!(
+            crate::decode_with_limit("BB8", 1).unwrap_err().to_string(),
+            "output would exceed the configured maximum length"
+        );
+    }
+
+    #[cfg(feature = "defmt")]
+    #[test]
+    fn errors_log_through_defmt() {
+        // Output needs a target logger; this checks the impls exist for `{}` and `{:?}` arguments
+        fn format<T: defmt::Format>(_: &T) {}
+        let err = decode("BB8A").unwrap_err();
+        format(&err);
+        format(&err.kind);
+    }
+
+    #[cfg(feature = "ufmt")]
+    #[test]
+    fn errors_write_through_ufmt() {
+        let err = decode("QED8WEX0BB8~").unwrap_err();
+        let mut out = alloc::string::String::new();
+        ufmt::uwrite!(out, "{}; {}", err, err.kind).unwrap();
+        assert_eq!(out, "InvalidChar at 11; InvalidChar");
+    }
+
+    #[test]
+    fn boxes_as_core_error() {
+        fn parse(
+            s: &str,
+        ) -> Result<alloc::vec::Vec<u8>, alloc::boxed::Box<dyn core::error::Error>> {
+            Ok(decode(s)?)
+        }
+        let err = parse("BB8ZZ").unwrap_err();
+        assert!(err.downcast_ref::<super::DecodeError>().is_some());
+    }
+}
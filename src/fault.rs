@@ -0,0 +1,220 @@
+//! Base45 text with one injected fault, for fuzzing and property tests of the error paths.
+
+use alloc::string::String;
+use alloc::vec::Vec;
+
+use crate::{ALPHABET_64, Base45String, DECODE_TABLE, ErrorKind, INVALID_DIGIT};
+
+/// Valid Base45 text with exactly one fault, and the error [`decode`](crate::decode) must report
+/// for it.
+///
+/// With the `arbitrary` feature it implements `Arbitrary`, so a fuzz target can check that every
+/// fault is caught where it was injected; with `testing`, `qr_base45::testing::almost_valid`
+/// generates it for proptest.
+///
+/// ```
+/// # #[cfg(feature = "arbitrary")] {
+/// use arbitrary::{Arbitrary, Unstructured};
+/// use qr_base45::AlmostValid;
+///
+/// let mut u = Unstructured::new(b"some fuzzer input");
+/// let case = AlmostValid::arbitrary(&mut u).unwrap();
+/// let err = qr_base45::decode(&case.text).unwrap_err();
+/// assert_eq!((err.kind, err.position), (case.kind, case.position));
+/// # }
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct AlmostValid {
+    /// The faulty text.
+    pub text: String,
+    /// The kind of error decoding `text` reports.
+    pub kind: ErrorKind,
+    /// The position decoding `text` reports.
+    pub position: usize,
+}
+
+/// The faults [`inject`] can introduce.
+pub(crate) const FAULTS: [ErrorKind; 4] = [
+    ErrorKind::InvalidChar,
+    ErrorKind::Overflow,
+    ErrorKind::TailOverflow,
+    ErrorKind::Dangling,
+];
+
+impl AlmostValid {
+    /// Break the valid encoding `text` with one of the faults of [`FAULTS`] that it can hold,
+    /// chosen by `choice` with equal weight; `Dangling` always applies. `pick` chooses the place
+    /// and `value` what is put there.
+    pub(crate) fn inject(text: Base45String, choice: u32, pick: u32, value: u32) -> AlmostValid {
+        let mut bytes = text.into_string().into_bytes();
+        let len = bytes.len();
+        let fitting = FAULTS.into_iter().filter(|&fault| fits(fault, len));
+        let fault = fitting.clone().nth(choice as usize % fitting.count());
+        let fault = fault.expect("a dangling character fits any valid text");
+        let position = inject(&mut bytes, fault, pick, value);
+        let text = String::from_utf8(bytes).expect("faults are ASCII");
+        AlmostValid {
+            text,
+            kind: fault,
+            position,
+        }
+    }
+}
+
+/// Whether valid Base45 text of `len` characters has room for `fault`.
+fn fits(fault: ErrorKind, len: usize) -> bool {
+    match fault {
+        ErrorKind::InvalidChar => len > 0,
+        ErrorKind::Overflow => len >= 3,
+        ErrorKind::TailOverflow => len % 3 == 2,
+        _ => true,
+    }
+}
+
+/// Inject `fault`, which [`fits`], into the valid Base45 ASCII `text`, returning the position
+/// decoding reports.
+fn inject(text: &mut Vec<u8>, fault: ErrorKind, pick: u32, value: u32) -> usize {
+    let digit = |d: u32| ALPHABET_64[d as usize];
+    match fault {
+        ErrorKind::InvalidChar => {
+            // Any ASCII character outside the alphabet
+            let outside = (0..0x80u8).filter(|&b| DECODE_TABLE[b as usize] == INVALID_DIGIT);
+            let at = pick as usize % text.len();
+            let n = value as usize % outside.clone().count();
+            text[at] = outside.clone().nth(n).expect("n is below the count");
+            at
+        }
+        ErrorKind::Overflow => {
+            // 65536..=91124: three digits, but more than 16 bits
+            let v = 65536 + value % (45 * 45 * 45 - 65536);
+            let at = pick as usize % (text.len() / 3) * 3;
+            text[at..at + 3].copy_from_slice(&[digit(v % 45), digit(v / 45 % 45), digit(v / 2025)]);
+            at
+        }
+        ErrorKind::TailOverflow => {
+            // 256..=2024: two digits, but more than 8 bits
+            let v = 256 + value % (45 * 45 - 256);
+            let at = text.len() - 2;
+            text[at..].copy_from_slice(&[digit(v % 45), digit(v / 45)]);
+            at
+        }
+        _ => {
+            // Dangling: drop the second character of a final pair, or add a lone one
+            if text.len() % 3 == 2 {
+                text.pop();
+            } else {
+                text.push(digit(value % 45));
+            }
+            text.len() - 1
+        }
+    }
+}
+
+#[cfg(feature = "arbitrary")]
+mod arbitrary_impls {
+    use alloc::vec::Vec;
+
+    use arbitrary::{Arbitrary, Result, Unstructured};
+
+    use super::AlmostValid;
+    use crate::Base45String;
+
+    /// The encoding of arbitrary bytes, so always valid.
+    impl<'a> Arbitrary<'a> for Base45String {
+        fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+            Ok(Base45String::encode(Vec::<u8>::arbitrary(u)?))
+        }
+
+        fn size_hint(depth: usize) -> (usize, Option<usize>) {
+            Vec::<u8>::size_hint(depth)
+        }
+    }
+
+    impl<'a> Arbitrary<'a> for AlmostValid {
+        fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+            let (choice, pick, value) = (u.arbitrary()?, u.arbitrary()?, u.arbitrary()?);
+            let text = Base45String::arbitrary(u)?;
+            Ok(AlmostValid::inject(text, choice, pick, value))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn every_fault_is_reported_where_injected() {
+        for len in 0..12 {
+            let text = Base45String::encode(alloc::vec![0xA5; len]);
+            for choice in 0..FAULTS.len() as u32 {
+                for (pick, value) in [(0, 0), (7, 1000), (u32::MAX, u32::MAX)] {
+                    let case = AlmostValid::inject(text.clone(), choice, pick, value);
+                    let err = crate::decode(&case.text).unwrap_err();
+                    assert_eq!(
+                        (err.kind, err.position),
+                        (case.kind, case.position),
+                        "{case:?}"
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn fitting_faults_are_equally_likely() {
+        for len in 0..12 {
+            let text = Base45String::encode(alloc::vec![0xA5; len]);
+            let fitting: Vec<ErrorKind> = FAULTS
+                .into_iter()
+                .filter(|&f| fits(f, text.len()))
+                .collect();
+            // 12 consecutive choices are a whole number of rounds for 1 to 4 fitting faults
+            let mut counts = [0; FAULTS.len()];
+            for choice in 0..12 {
+                let kind = AlmostValid::inject(text.clone(), choice, 0, 0).kind;
+                counts[FAULTS.iter().position(|&f| f == kind).unwrap()] += 1;
+            }
+            for (fault, count) in FAULTS.into_iter().zip(counts) {
+                let expected = if fitting.contains(&fault) {
+                    12 / fitting.len()
+                } else {
+                    0
+                };
+                assert_eq!(count, expected, "{fault:?} for {len} bytes");
+            }
+        }
+    }
+
+    #[cfg(feature = "arbitrary")]
+    #[test]
+    fn arbitrary_cases_match_decode() {
+        use arbitrary::{Arbitrary, Unstructured};
+
+        let mut kinds = Vec::new();
+        let mut state = 0x2545_F491u32;
+        for _ in 0..256 {
+            // xorshift, for inputs that vary like a fuzzer's
+            let data: Vec<u8> = (0..64)
+                .map(|_| {
+                    state ^= state << 13;
+                    state ^= state >> 17;
+                    state ^= state << 5;
+                    state as u8
+                })
+                .collect();
+            let mut u = Unstructured::new(&data);
+            let valid = Base45String::arbitrary(&mut u).unwrap();
+            assert!(crate::decode(&valid).is_ok());
+            let case = AlmostValid::arbitrary(&mut Unstructured::new(&data)).unwrap();
+            let err = crate::decode(&case.text).unwrap_err();
+            assert_eq!(
+                (err.kind, err.position),
+                (case.kind, case.position),
+                "{case:?}"
+            );
+            kinds.push(case.kind);
+        }
+        assert!(FAULTS.iter().all(|kind| kinds.contains(kind)), "{kinds:?}");
+    }
+}
@@ -0,0 +1,131 @@
+//! Encoding and decoding into fixed-capacity containers, for firmware with static memory budgets.
+//! Input that would not fit is rejected with `BufferTooSmall` before anything is written.
+
+#[cfg(feature = "arrayvec")]
+pub use self::arrayvec_impls::{decode_to_array_vec, encode_to_array_string};
+#[cfg(feature = "heapless")]
+pub use self::heapless_impls::{decode_to_heapless, encode_to_heapless};
+
+#[cfg(feature = "arrayvec")]
+mod arrayvec_impls {
+    use ::arrayvec::{ArrayString, ArrayVec};
+
+    use crate::{Base45Error, ErrorKind, decode_slice, encode_fmt, encoded_len};
+
+    /// Encode `input` into an `ArrayString<CAP>`, or `BufferTooSmall` if the encoding is longer
+    /// than `CAP` characters.
+    ///
+    /// ```
+    /// let s = qr_base45::encode_to_array_string::<8>(b"ietf!").unwrap();
+    /// assert_eq!(s.as_str(), "QED8WEX0");
+    /// ```
+    pub fn encode_to_array_string<const CAP: usize>(
+        input: impl AsRef<[u8]>,
+    ) -> Result<ArrayString<CAP>, Base45Error> {
+        let input = input.as_ref();
+        if encoded_len(input.len()) > CAP {
+            return Err(Base45Error::new(ErrorKind::BufferTooSmall));
+        }
+        let mut out = ArrayString::new();
+        encode_fmt(input, &mut out).expect("checked to fit");
+        Ok(out)
+    }
+
+    /// Decode `s` into an `ArrayVec<u8, CAP>`, or `BufferTooSmall` if it decodes to more than
+    /// `CAP` bytes.
+    ///
+    /// ```
+    /// let v = qr_base45::decode_to_array_vec::<5>("QED8WEX0").unwrap();
+    /// assert_eq!(v.as_slice(), b"ietf!");
+    /// ```
+    pub fn decode_to_array_vec<const CAP: usize>(
+        s: &str,
+    ) -> Result<ArrayVec<u8, CAP>, Base45Error> {
+        let len = s.len() / 3 * 2 + s.len() % 3 / 2;
+        if len > CAP {
+            return Err(Base45Error::new(ErrorKind::BufferTooSmall));
+        }
+        let mut out: ArrayVec<u8, CAP> = core::iter::repeat_n(0, len).collect();
+        decode_slice(s, &mut out)?;
+        Ok(out)
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+
+        #[test]
+        fn arrayvec_roundtrip_and_capacity() {
+            let s = encode_to_array_string::<8>(b"ietf!").unwrap();
+            assert_eq!(s.as_str(), "QED8WEX0");
+            assert_eq!(decode_to_array_vec::<5>(&s).unwrap().as_slice(), b"ietf!");
+
+            let full = |e: Base45Error| e.kind == ErrorKind::BufferTooSmall;
+            assert!(encode_to_array_string::<7>(b"ietf!").is_err_and(full));
+            assert!(decode_to_array_vec::<4>("QED8WEX0").is_err_and(full));
+            let err = decode_to_array_vec::<5>("QED8WEx0").unwrap_err();
+            assert_eq!((err.kind, err.position), (ErrorKind::InvalidChar, 6));
+        }
+    }
+}
+
+#[cfg(feature = "heapless")]
+mod heapless_impls {
+    use ::heapless::{String, Vec};
+
+    use crate::{Base45Error, ErrorKind, decode_slice, encode_slice, encoded_len};
+
+    /// Encode `input` into a `heapless::String<N>`, or `BufferTooSmall` if the encoding is longer
+    /// than `N` characters.
+    ///
+    /// ```
+    /// let s = qr_base45::encode_to_heapless::<8>(b"ietf!").unwrap();
+    /// assert_eq!(s, "QED8WEX0");
+    /// assert!(qr_base45::encode_to_heapless::<7>(b"ietf!").is_err());
+    /// ```
+    pub fn encode_to_heapless<const N: usize>(
+        input: impl AsRef<[u8]>,
+    ) -> Result<String<N>, Base45Error> {
+        let input = input.as_ref();
+        let mut out = Vec::<u8, N>::new();
+        out.resize(encoded_len(input.len()), 0)
+            .map_err(|_| Base45Error::new(ErrorKind::BufferTooSmall))?;
+        encode_slice(input, &mut out)?;
+        Ok(String::from_utf8(out).expect("Base45 output is ASCII"))
+    }
+
+    /// Decode `s` into a `heapless::Vec<u8, N>`, or `BufferTooSmall` if it decodes to more than
+    /// `N` bytes.
+    ///
+    /// ```
+    /// let v = qr_base45::decode_to_heapless::<5>("QED8WEX0").unwrap();
+    /// assert_eq!(v, b"ietf!");
+    /// ```
+    pub fn decode_to_heapless<const N: usize>(s: &str) -> Result<Vec<u8, N>, Base45Error> {
+        let mut out = Vec::<u8, N>::new();
+        let len = s.len() / 3 * 2 + s.len() % 3 / 2;
+        out.resize(len, 0)
+            .map_err(|_| Base45Error::new(ErrorKind::BufferTooSmall))?;
+        decode_slice(s, &mut out)?;
+        Ok(out)
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+
+        #[test]
+        fn heapless_roundtrip_and_capacity() {
+            let s = encode_to_heapless::<8>(b"ietf!").unwrap();
+            assert_eq!(s, "QED8WEX0");
+            assert_eq!(decode_to_heapless::<5>(&s).unwrap(), b"ietf!");
+            assert_eq!(encode_to_heapless::<0>(b"").unwrap(), "");
+
+            let full = |e: Base45Error| e.kind == ErrorKind::BufferTooSmall;
+            assert!(encode_to_heapless::<7>(b"ietf!").is_err_and(full));
+            assert!(decode_to_heapless::<4>("QED8WEX0").is_err_and(full));
+            let err = decode_to_heapless::<5>("QED8WEx0").unwrap_err();
+            assert_eq!((err.kind, err.position), (ErrorKind::InvalidChar, 6));
+        }
+    }
+}
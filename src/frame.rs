@@ -0,0 +1,286 @@
+//! Fixed-size frames for shipping a Base45 payload over small-packet links such as UART or BLE.
+//!
+//! Each frame is a 1-byte header followed by up to `frame_len - 1` payload characters. Bit 7 of
+//! the header marks the last frame and bits 0-6 hold the sequence number, so a payload spans at
+//! most [`MAX_FRAMES`] frames. Every frame but the last carries a full `frame_len - 1` characters.
+//!
+//! ```
+//! use qr_base45::frame::{Reassembler, split};
+//!
+//! let payload = qr_base45::encode(b"Hello over BLE!");
+//! let frames: Vec<Vec<u8>> = split(&payload, 8)
+//!     .unwrap()
+//!     .map(|f| f.iter().collect())
+//!     .collect();
+//!
+//! // Frames may arrive out of order and more than once
+//! let mut buf = [0u8; 64];
+//! let mut rx = Reassembler::new(&mut buf, 8);
+//! for frame in frames.iter().rev().chain(&frames) {
+//!     rx.push(frame).unwrap();
+//! }
+//! assert_eq!(rx.finish().unwrap(), b"Hello over BLE!");
+//! ```
+
+use crate::{Base45Error, ErrorKind, decode_bytes_in_place};
+
+/// Header bit marking the last frame of a payload.
+pub const LAST: u8 = 0x80;
+/// Maximum number of frames in one payload.
+pub const MAX_FRAMES: usize = 128;
+
+/// Split the Base45 text `encoded` into frames of at most `frame_len` bytes, header included.
+///
+/// Returns `InvalidLength` if `frame_len` is below 2, and `TooLarge` if the payload needs more
+/// than [`MAX_FRAMES`] frames. An empty payload is sent as a single empty last frame.
+pub fn split(encoded: &str, frame_len: usize) -> Result<Frames<'_>, Base45Error> {
+    if frame_len < 2 {
+        return Err(Base45Error::new(ErrorKind::InvalidLength));
+    }
+    let chunk = frame_len - 1;
+    if encoded.len().div_ceil(chunk) > MAX_FRAMES {
+        return Err(Base45Error::new(ErrorKind::TooLarge));
+    }
+    Ok(Frames {
+        rest: encoded.as_bytes(),
+        chunk,
+        seq: 0,
+        done: false,
+    })
+}
+
+/// Iterator over the frames of a payload. Created by [`split`].
+#[derive(Debug, Clone)]
+pub struct Frames<'a> {
+    rest: &'a [u8],
+    chunk: usize,
+    seq: u8,
+    done: bool,
+}
+
+impl<'a> Iterator for Frames<'a> {
+    type Item = Frame<'a>;
+
+    fn next(&mut self) -> Option<Frame<'a>> {
+        if self.done {
+            return None;
+        }
+        let (payload, rest) = self.rest.split_at(self.chunk.min(self.rest.len()));
+        self.rest = rest;
+        self.done = rest.is_empty();
+        let header = self.seq | if self.done { LAST } else { 0 };
+        self.seq += 1;
+        Some(Frame { header, payload })
+    }
+}
+
+impl core::iter::FusedIterator for Frames<'_> {}
+
+/// One frame: a header byte and a slice of the payload.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Frame<'a> {
+    header: u8,
+    payload: &'a [u8],
+}
+
+impl<'a> Frame<'a> {
+    /// The header byte: sequence number, plus [`LAST`] on the last frame.
+    pub const fn header(&self) -> u8 {
+        self.header
+    }
+
+    /// Position of this frame in the payload, from 0.
+    pub const fn seq(&self) -> u8 {
+        self.header & !LAST
+    }
+
+    /// Whether this is the last frame of the payload.
+    pub const fn is_last(&self) -> bool {
+        self.header & LAST != 0
+    }
+
+    /// The payload characters carried by this frame.
+    pub const fn payload(&self) -> &'a [u8] {
+        self.payload
+    }
+
+    /// Length of the frame on the wire, header included.
+    #[allow(clippy::len_without_is_empty)]
+    pub const fn len(&self) -> usize {
+        1 + self.payload.len()
+    }
+
+    /// The bytes of the frame on the wire: the header, then the payload.
+    pub fn iter(&self) -> impl Iterator<Item = u8> + 'a {
+        core::iter::once(self.header).chain(self.payload.iter().copied())
+    }
+
+    /// Copy the frame into `out`, returning its length, or `BufferTooSmall` if it does not fit.
+    pub fn copy_to(&self, out: &mut [u8]) -> Result<usize, Base45Error> {
+        let Some((header, payload)) = out
+            .get_mut(..self.len())
+            .and_then(|out| out.split_first_mut())
+        else {
+            return Err(Base45Error::new(ErrorKind::BufferTooSmall));
+        };
+        *header = self.header;
+        payload.copy_from_slice(self.payload);
+        Ok(self.len())
+    }
+}
+
+/// Collects frames in any order into a caller-provided buffer and decodes the payload once all
+/// of them have arrived. Duplicate frames are ignored.
+#[derive(Debug)]
+pub struct Reassembler<'a> {
+    buf: &'a mut [u8],
+    chunk: usize,
+    received: u128,
+    /// Sequence number and payload length of the last frame, once seen
+    last: Option<(u8, usize)>,
+}
+
+impl<'a> Reassembler<'a> {
+    /// Reassemble frames of `frame_len` bytes (as passed to [`split`]) into `buf`, which must
+    /// hold the whole Base45 payload.
+    pub fn new(buf: &'a mut [u8], frame_len: usize) -> Reassembler<'a> {
+        Reassembler {
+            buf,
+            chunk: frame_len.saturating_sub(1),
+            received: 0,
+            last: None,
+        }
+    }
+
+    /// Accept one frame as received, returning whether the payload is now complete.
+    ///
+    /// Returns `InvalidFrame` for an empty or oversized frame, a short frame that is not the
+    /// last, or a frame that contradicts the last one; `BufferTooSmall` if the frame lies beyond
+    /// the buffer. A rejected frame leaves the state unchanged.
+    pub fn push(&mut self, frame: &[u8]) -> Result<bool, Base45Error> {
+        let invalid = || Base45Error::new(ErrorKind::InvalidFrame);
+        let (&header, payload) = frame.split_first().ok_or_else(invalid)?;
+        let seq = header & !LAST;
+        let is_last = header & LAST != 0;
+        if payload.len() > self.chunk || (!is_last && payload.len() != self.chunk) {
+            return Err(invalid());
+        }
+        match self.last {
+            Some(last) if is_last && last != (seq, payload.len()) => return Err(invalid()),
+            Some((n, _)) if seq > n => return Err(invalid()),
+            None if is_last && self.received >> seq > 1 => return Err(invalid()),
+            _ => {}
+        }
+        let start = seq as usize * self.chunk;
+        let Some(dst) = self.buf.get_mut(start..start + payload.len()) else {
+            return Err(Base45Error::new(ErrorKind::BufferTooSmall));
+        };
+        if self.received & 1 << seq == 0 {
+            dst.copy_from_slice(payload);
+            self.received |= 1 << seq;
+        }
+        if is_last {
+            self.last = Some((seq, payload.len()));
+        }
+        Ok(self.is_complete())
+    }
+
+    /// Whether the last frame and every frame before it have arrived.
+    pub fn is_complete(&self) -> bool {
+        self.last
+            .is_some_and(|(n, _)| self.received == u128::MAX >> (127 - n))
+    }
+
+    /// The reassembled Base45 text, once complete.
+    pub fn payload(&self) -> Option<&[u8]> {
+        let (n, len) = self.last.filter(|_| self.is_complete())?;
+        self.buf.get(..n as usize * self.chunk + len)
+    }
+
+    /// Decode the reassembled payload in place, returning the decoded bytes.
+    ///
+    /// Returns `InvalidFrame` if frames are still missing.
+    pub fn finish(self) -> Result<&'a mut [u8], Base45Error> {
+        let Some((n, len)) = self.last.filter(|_| self.is_complete()) else {
+            return Err(Base45Error::new(ErrorKind::InvalidFrame));
+        };
+        let text = &mut self.buf[..n as usize * self.chunk + len];
+        let len = decode_bytes_in_place(text)?;
+        Ok(&mut text[..len])
+    }
+}
+
+#[cfg(all(test, feature = "alloc"))]
+mod tests {
+    use super::*;
+    use alloc::vec::Vec;
+
+    fn frames(payload: &str, frame_len: usize) -> Vec<Vec<u8>> {
+        split(payload, frame_len)
+            .unwrap()
+            .map(|f| f.iter().collect())
+            .collect()
+    }
+
+    #[test]
+    fn split_sets_headers() {
+        let f = frames("QED8WEX0", 4);
+        assert_eq!(f, [&b"\x00QED"[..], b"\x018WE", b"\x82X0"]);
+        assert_eq!(frames("", 20), [[LAST]]);
+        assert_eq!(frames("QED", 4), [b"\x80QED"]);
+
+        let frame = split("QED8WEX0", 4).unwrap().last().unwrap();
+        let mut out = [0u8; 3];
+        assert_eq!(frame.copy_to(&mut out), Ok(3));
+        assert_eq!((frame.seq(), frame.is_last(), &out), (2, true, b"\x82X0"));
+        assert_eq!(
+            frame.copy_to(&mut out[..2]).unwrap_err().kind,
+            ErrorKind::BufferTooSmall
+        );
+
+        assert_eq!(split("QED", 1).unwrap_err().kind, ErrorKind::InvalidLength);
+        let long = "0".repeat(MAX_FRAMES * 2 + 1);
+        assert_eq!(split(&long, 3).unwrap_err().kind, ErrorKind::TooLarge);
+        assert_eq!(split(&long[1..], 3).unwrap().count(), MAX_FRAMES);
+    }
+
+    #[test]
+    fn reassembler_tolerates_reordering_and_duplicates() {
+        let data: Vec<u8> = (0..=255).collect();
+        let payload = crate::encode(&data);
+        let f = frames(&payload, 20);
+        let mut buf = [0u8; 400];
+        let mut rx = Reassembler::new(&mut buf, 20);
+        for (i, frame) in f.iter().enumerate().rev() {
+            assert_eq!(rx.push(frame), Ok(i == 0));
+            assert_eq!(rx.push(frame), Ok(i == 0));
+        }
+        assert_eq!(rx.payload(), Some(payload.as_bytes()));
+        assert_eq!(rx.finish().unwrap(), data);
+    }
+
+    #[test]
+    fn reassembler_rejects_bad_frames() {
+        let f = frames("QED8WEX0", 4);
+        let mut buf = [0u8; 16];
+        let mut rx = Reassembler::new(&mut buf, 4);
+        let kind = |r: Result<bool, Base45Error>| r.unwrap_err().kind;
+        assert_eq!(kind(rx.push(b"")), ErrorKind::InvalidFrame);
+        assert_eq!(kind(rx.push(b"\x00QE")), ErrorKind::InvalidFrame);
+        assert_eq!(kind(rx.push(b"\x00QED8")), ErrorKind::InvalidFrame);
+        assert_eq!(kind(rx.push(b"\x09QED")), ErrorKind::BufferTooSmall);
+        assert_eq!(rx.push(&f[2]), Ok(false));
+        assert_eq!(kind(rx.push(b"\x83X0")), ErrorKind::InvalidFrame);
+        assert_eq!(kind(rx.push(b"\x82X")), ErrorKind::InvalidFrame);
+        assert_eq!(kind(rx.push(b"\x03QED")), ErrorKind::InvalidFrame);
+        assert_eq!(rx.push(&f[0]), Ok(false));
+        assert_eq!(rx.payload(), None);
+        assert_eq!(rx.finish().unwrap_err().kind, ErrorKind::InvalidFrame);
+
+        // A last frame cannot come before frames already received
+        let mut buf = [0u8; 16];
+        let mut rx = Reassembler::new(&mut buf, 4);
+        assert_eq!(rx.push(&f[1]), Ok(false));
+        assert_eq!(kind(rx.push(b"\x80QE")), ErrorKind::InvalidFrame);
+    }
+}
@@ -0,0 +1,379 @@
+//! `futures` stream and sink transformers for message pipelines such as WebSockets: each chunk
+//! of raw bytes becomes a chunk of Base45 text, and back.
+//!
+//! Chunks may split groups anywhere; the part of a group at the end of one chunk is carried into
+//! the next by a [`StreamEncoder`] or [`StreamDecoder`], so the concatenated output equals the
+//! one-shot encoding or decoding of the concatenated input. Chunks that complete no group produce
+//! no item.
+//!
+//! ```
+//! use bytes::Bytes;
+//! use futures::{StreamExt, stream};
+//! use qr_base45::futures::{DecodeChunks, EncodeChunks};
+//!
+//! # futures::executor::block_on(async {
+//! let chunks = stream::iter([Bytes::from_static(b"iet"), Bytes::from_static(b"f!")]);
+//! let text: Vec<String> = EncodeChunks::new(chunks).collect().await;
+//! assert_eq!(text, ["QED", "8WE", "X0"]);
+//!
+//! let chunks = stream::iter(["QED8", "WEX0"]);
+//! let bytes: Vec<Bytes> = DecodeChunks::new(chunks).map(Result::unwrap).collect().await;
+//! assert_eq!(bytes, [&b"ie"[..], b"tf", b"!"]);
+//! # });
+//! ```
+
+use core::pin::Pin;
+use core::task::{Context, Poll, ready};
+
+use ::futures_core::Stream;
+use ::futures_sink::Sink;
+use bytes::Bytes;
+
+use crate::{Base45Error, StreamDecoder, StreamEncoder, char_err_at};
+
+/// Encode one chunk, or the end of the input, into a `String`.
+fn text(chars: impl Iterator<Item = char>) -> Option<String> {
+    let s: String = chars.collect();
+    (!s.is_empty()).then_some(s)
+}
+
+/// Decode one chunk into `Bytes`; the first error replaces the whole chunk.
+fn bytes(dec: &mut StreamDecoder, chunk: &[u8]) -> Option<Result<Bytes, Base45Error>> {
+    let at = dec.offset();
+    let out: Result<Vec<u8>, _> = dec.push(chunk).collect();
+    match out {
+        Ok(out) if out.is_empty() => None,
+        out => Some(out.map(Bytes::from).map_err(char_err_at(chunk, at))),
+    }
+}
+
+/// Decode the final partial group.
+fn last_byte(dec: &mut StreamDecoder) -> Result<Option<Bytes>, Base45Error> {
+    Ok(dec.finish()?.map(|b| Bytes::copy_from_slice(&[b])))
+}
+
+/// A stream of Base45 text chunks encoding the byte chunks of the inner stream.
+#[derive(Debug)]
+pub struct EncodeChunks<S> {
+    inner: S,
+    enc: StreamEncoder,
+    done: bool,
+}
+
+impl<S> EncodeChunks<S> {
+    /// Encode the chunks of `inner`.
+    pub fn new(inner: S) -> Self {
+        EncodeChunks {
+            inner,
+            enc: StreamEncoder::new(),
+            done: false,
+        }
+    }
+
+    /// The inner stream.
+    pub fn get_ref(&self) -> &S {
+        &self.inner
+    }
+
+    /// The inner stream. Polling it directly skips those chunks.
+    pub fn get_mut(&mut self) -> &mut S {
+        &mut self.inner
+    }
+
+    /// Unwrap the inner stream, dropping a held odd byte.
+    pub fn into_inner(self) -> S {
+        self.inner
+    }
+}
+
+impl<S, B> Stream for EncodeChunks<S>
+where
+    S: Stream<Item = B> + Unpin,
+    B: AsRef<[u8]>,
+{
+    type Item = String;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<String>> {
+        let this = self.get_mut();
+        while !this.done {
+            match ready!(Pin::new(&mut this.inner).poll_next(cx)) {
+                Some(chunk) => {
+                    if let Some(s) = text(this.enc.push(chunk.as_ref())) {
+                        return Poll::Ready(Some(s));
+                    }
+                }
+                None => {
+                    this.done = true;
+                    return Poll::Ready(text(this.enc.finish()));
+                }
+            }
+        }
+        Poll::Ready(None)
+    }
+}
+
+/// A stream of the byte chunks decoded from the Base45 text chunks of the inner stream.
+///
+/// Error positions are offsets in the whole text; the first error ends the stream.
+#[derive(Debug)]
+pub struct DecodeChunks<S> {
+    inner: S,
+    dec: StreamDecoder,
+    done: bool,
+}
+
+impl<S> DecodeChunks<S> {
+    /// Decode the chunks of `inner`.
+    pub fn new(inner: S) -> Self {
+        DecodeChunks {
+            inner,
+            dec: StreamDecoder::new(),
+            done: false,
+        }
+    }
+
+    /// The inner stream.
+    pub fn get_ref(&self) -> &S {
+        &self.inner
+    }
+
+    /// The inner stream. Polling it directly skips those chunks.
+    pub fn get_mut(&mut self) -> &mut S {
+        &mut self.inner
+    }
+
+    /// Unwrap the inner stream, dropping a held partial group.
+    pub fn into_inner(self) -> S {
+        self.inner
+    }
+}
+
+impl<S, T> Stream for DecodeChunks<S>
+where
+    S: Stream<Item = T> + Unpin,
+    T: AsRef<[u8]>,
+{
+    type Item = Result<Bytes, Base45Error>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = self.get_mut();
+        while !this.done {
+            match ready!(Pin::new(&mut this.inner).poll_next(cx)) {
+                Some(chunk) => {
+                    if let Some(out) = bytes(&mut this.dec, chunk.as_ref()) {
+                        this.done = out.is_err();
+                        return Poll::Ready(Some(out));
+                    }
+                }
+                None => {
+                    this.done = true;
+                    return Poll::Ready(last_byte(&mut this.dec).transpose());
+                }
+            }
+        }
+        Poll::Ready(None)
+    }
+}
+
+/// A sink of `Bytes` chunks that sends their Base45 encoding to the inner sink of text chunks.
+/// Closing it sends the characters of a held odd byte.
+#[derive(Debug)]
+pub struct EncodeSink<S> {
+    inner: S,
+    enc: StreamEncoder,
+}
+
+impl<S> EncodeSink<S> {
+    /// Encode into `inner`.
+    pub fn new(inner: S) -> Self {
+        EncodeSink {
+            inner,
+            enc: StreamEncoder::new(),
+        }
+    }
+
+    /// The inner sink.
+    pub fn get_ref(&self) -> &S {
+        &self.inner
+    }
+
+    /// The inner sink. Sending to it directly interleaves with the encoded chunks.
+    pub fn get_mut(&mut self) -> &mut S {
+        &mut self.inner
+    }
+
+    /// Unwrap the inner sink, dropping a held odd byte.
+    pub fn into_inner(self) -> S {
+        self.inner
+    }
+}
+
+impl<S: Sink<String> + Unpin> Sink<Bytes> for EncodeSink<S> {
+    type Error = S::Error;
+
+    fn poll_ready(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<(), S::Error>> {
+        Pin::new(&mut self.get_mut().inner).poll_ready(cx)
+    }
+
+    fn start_send(self: Pin<&mut Self>, item: Bytes) -> Result<(), S::Error> {
+        let this = self.get_mut();
+        match text(this.enc.push(&item)) {
+            Some(s) => Pin::new(&mut this.inner).start_send(s),
+            None => Ok(()),
+        }
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<(), S::Error>> {
+        Pin::new(&mut self.get_mut().inner).poll_flush(cx)
+    }
+
+    fn poll_close(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<(), S::Error>> {
+        let this = self.get_mut();
+        if this.enc.has_pending() {
+            ready!(Pin::new(&mut this.inner).poll_ready(cx))?;
+            if let Some(s) = text(this.enc.finish()) {
+                Pin::new(&mut this.inner).start_send(s)?;
+            }
+        }
+        Pin::new(&mut this.inner).poll_close(cx)
+    }
+}
+
+/// A sink of Base45 text chunks (`String`s) that sends the decoded bytes to the inner sink of
+/// byte chunks. Closing it decodes a held final group.
+///
+/// Decode failures are returned as the inner sink's error, which must convert from
+/// [`Base45Error`]; positions are offsets in the whole text.
+#[derive(Debug)]
+pub struct DecodeSink<S> {
+    inner: S,
+    dec: StreamDecoder,
+}
+
+impl<S> DecodeSink<S> {
+    /// Decode into `inner`.
+    pub fn new(inner: S) -> Self {
+        DecodeSink {
+            inner,
+            dec: StreamDecoder::new(),
+        }
+    }
+
+    /// The inner sink.
+    pub fn get_ref(&self) -> &S {
+        &self.inner
+    }
+
+    /// The inner sink. Sending to it directly interleaves with the decoded chunks.
+    pub fn get_mut(&mut self) -> &mut S {
+        &mut self.inner
+    }
+
+    /// Unwrap the inner sink, dropping a held partial group.
+    pub fn into_inner(self) -> S {
+        self.inner
+    }
+}
+
+impl<S> Sink<String> for DecodeSink<S>
+where
+    S: Sink<Bytes> + Unpin,
+    S::Error: From<Base45Error>,
+{
+    type Error = S::Error;
+
+    fn poll_ready(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<(), S::Error>> {
+        Pin::new(&mut self.get_mut().inner).poll_ready(cx)
+    }
+
+    fn start_send(self: Pin<&mut Self>, item: String) -> Result<(), S::Error> {
+        let this = self.get_mut();
+        match bytes(&mut this.dec, item.as_bytes()) {
+            Some(out) => Pin::new(&mut this.inner).start_send(out?),
+            None => Ok(()),
+        }
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<(), S::Error>> {
+        Pin::new(&mut self.get_mut().inner).poll_flush(cx)
+    }
+
+    fn poll_close(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<(), S::Error>> {
+        let this = self.get_mut();
+        // The decoder resets once finished, so a repeated close sends nothing more
+        ready!(Pin::new(&mut this.inner).poll_ready(cx))?;
+        if let Some(b) = last_byte(&mut this.dec)? {
+            Pin::new(&mut this.inner).start_send(b)?;
+        }
+        Pin::new(&mut this.inner).poll_close(cx)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ErrorKind;
+    use futures::executor::block_on;
+    use futures::{SinkExt, StreamExt, stream};
+
+    /// `data` split into chunks of `step` bytes.
+    fn chunks(data: &[u8], step: usize) -> Vec<Bytes> {
+        data.chunks(step).map(Bytes::copy_from_slice).collect()
+    }
+
+    #[test]
+    fn chunks_match_one_shot_calls() {
+        let data: Vec<u8> = (0..301u32).map(|i| (i * 167 + 13) as u8).collect();
+        let text = crate::encode(&data);
+        block_on(async {
+            for step in [1, 2, 3, 7, 1000] {
+                let s = EncodeChunks::new(stream::iter(chunks(&data, step)));
+                assert_eq!(s.collect::<Vec<_>>().await.concat(), text);
+
+                let s = DecodeChunks::new(stream::iter(chunks(text.as_bytes(), step)));
+                let out: Result<Vec<Bytes>, _> = s.collect::<Vec<_>>().await.into_iter().collect();
+                assert_eq!(out.unwrap().concat(), data);
+
+                let mut sink = EncodeSink::new(Vec::<String>::new());
+                for chunk in chunks(&data, step) {
+                    sink.send(chunk).await.unwrap();
+                }
+                sink.close().await.unwrap();
+                assert_eq!(sink.into_inner().concat(), text);
+
+                let inner = Vec::<Bytes>::new().sink_map_err(|e| match e {});
+                let mut sink = DecodeSink::new(inner);
+                for chunk in text.as_bytes().chunks(step) {
+                    let chunk = String::from_utf8(chunk.to_vec()).unwrap();
+                    let r: Result<(), Base45Error> = sink.send(chunk).await;
+                    r.unwrap();
+                }
+                sink.close().await.unwrap();
+                assert_eq!(sink.into_inner().into_inner().concat(), data);
+            }
+        });
+    }
+
+    #[test]
+    fn decode_errors_end_the_stream() {
+        block_on(async {
+            let s = DecodeChunks::new(stream::iter(["QED8W", "EXa", "BB8"]));
+            let out: Vec<_> = s.collect().await;
+            assert_eq!(out.len(), 2);
+            let err = out[1].as_ref().unwrap_err();
+            assert_eq!((err.kind, err.position), (ErrorKind::InvalidChar, 7));
+            assert_eq!(err.ch(), Some('a'));
+
+            let s = DecodeChunks::new(stream::iter(["QED8WEX"]));
+            let out: Vec<_> = s.collect().await;
+            assert_eq!(out[1].as_ref().unwrap_err().kind, ErrorKind::Dangling);
+
+            let inner = Vec::<Bytes>::new().sink_map_err(|e| match e {});
+            let mut sink = DecodeSink::new(inner);
+            let r: Result<(), Base45Error> = sink.send("BB8A".into()).await;
+            r.unwrap();
+            let err: Base45Error = sink.close().await.unwrap_err();
+            assert_eq!((err.kind, err.position), (ErrorKind::Dangling, 3));
+        });
+    }
+}
@@ -0,0 +1,153 @@
+//! `futures::io` adapters, the async counterparts of
+//! [`Base45EncodeReader`](crate::Base45EncodeReader) and friends for the `futures-io` traits.
+//!
+//! They behave like the `tokio` module's adapters: partial groups are buffered across polls,
+//! writers end the stream in `poll_close` (dropping one first loses the final group), and decode
+//! failures are sticky `InvalidData` errors wrapping the [`Base45Error`](crate::Base45Error).
+//!
+//! ```
+//! use futures::io::{AsyncReadExt, AsyncWriteExt};
+//! use qr_base45::futures_io::{Base45DecodeReader, Base45EncodeWriter};
+//!
+//! # futures::executor::block_on(async {
+//! let mut w = Base45EncodeWriter::new(Vec::new());
+//! w.write_all(b"ietf!").await?;
+//! w.close().await?;
+//! assert_eq!(w.get_ref(), b"QED8WEX0");
+//!
+//! let mut out = Vec::new();
+//! Base45DecodeReader::new(&b"QED8WEX0"[..]).read_to_end(&mut out).await?;
+//! assert_eq!(out, b"ietf!");
+//! # Ok::<(), std::io::Error>(())
+//! # }).unwrap();
+//! ```
+
+use core::pin::Pin;
+use core::task::{Context, Poll, ready};
+use std::io;
+
+use ::futures_io::{AsyncRead, AsyncWrite};
+
+use crate::pump::{Pump, adapter};
+
+macro_rules! impl_read {
+    ($name:ident) => {
+        impl<R: AsyncRead + Unpin> AsyncRead for $name<R> {
+            fn poll_read(
+                self: Pin<&mut Self>,
+                cx: &mut Context<'_>,
+                buf: &mut [u8],
+            ) -> Poll<io::Result<usize>> {
+                let this = self.get_mut();
+                let read = |space: &mut [u8]| Pin::new(&mut this.inner).poll_read(cx, space);
+                this.pump.poll_read(read, buf)
+            }
+        }
+    };
+}
+
+macro_rules! impl_write {
+    ($name:ident) => {
+        impl<W: AsyncWrite + Unpin> AsyncWrite for $name<W> {
+            fn poll_write(
+                self: Pin<&mut Self>,
+                cx: &mut Context<'_>,
+                buf: &[u8],
+            ) -> Poll<io::Result<usize>> {
+                let this = self.get_mut();
+                let write = |out: &[u8]| Pin::new(&mut this.inner).poll_write(cx, out);
+                this.pump.poll_write(write, buf)
+            }
+
+            /// Write pending output and flush the inner writer. A partial group stays held:
+            /// only closing can end the stream.
+            fn poll_flush(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+                let this = self.get_mut();
+                let write = |out: &[u8]| Pin::new(&mut this.inner).poll_write(cx, out);
+                ready!(this.pump.poll_drain(write))?;
+                Pin::new(&mut this.inner).poll_flush(cx)
+            }
+
+            fn poll_close(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+                let this = self.get_mut();
+                let write = |out: &[u8]| Pin::new(&mut this.inner).poll_write(cx, out);
+                ready!(this.pump.poll_finish(write))?;
+                Pin::new(&mut this.inner).poll_close(cx)
+            }
+        }
+    };
+}
+
+adapter!(
+    reader,
+    /// An `AsyncRead` adapter: reads raw bytes from the inner reader and yields their Base45
+    /// encoding.
+    Base45EncodeReader,
+    encoder
+);
+impl_read!(Base45EncodeReader);
+
+adapter!(
+    reader,
+    /// An `AsyncRead` adapter: reads Base45 text from the inner reader and yields the decoded
+    /// bytes.
+    Base45DecodeReader,
+    decoder
+);
+impl_read!(Base45DecodeReader);
+
+adapter!(
+    writer,
+    /// An `AsyncWrite` adapter: takes raw bytes and writes their Base45 encoding to the inner
+    /// writer.
+    Base45EncodeWriter,
+    encoder
+);
+impl_write!(Base45EncodeWriter);
+
+adapter!(
+    writer,
+    /// An `AsyncWrite` adapter: takes Base45 text and writes the decoded bytes to the inner
+    /// writer.
+    Base45DecodeWriter,
+    decoder
+);
+impl_write!(Base45DecodeWriter);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futures::io::{AsyncReadExt, AsyncWriteExt};
+
+    // Splitting and sticky errors are covered by the `pump` tests
+    #[test]
+    fn adapters_round_trip() {
+        let data: Vec<u8> = (0..1001u32).map(|i| (i * 167 + 13) as u8).collect();
+        let text = crate::encode(&data);
+        futures::executor::block_on(async {
+            let mut out = String::new();
+            let mut r = Base45EncodeReader::new(&data[..]);
+            r.read_to_string(&mut out).await.unwrap();
+            assert_eq!(out, text);
+
+            let mut out = Vec::new();
+            let mut r = Base45DecodeReader::new(text.as_bytes());
+            r.read_to_end(&mut out).await.unwrap();
+            assert_eq!(out, data);
+
+            let mut w = Base45EncodeWriter::new(Vec::new());
+            w.write_all(&data).await.unwrap();
+            w.close().await.unwrap();
+            assert_eq!(w.into_inner(), text.as_bytes());
+
+            let mut w = Base45DecodeWriter::new(Vec::new());
+            w.write_all(text.as_bytes()).await.unwrap();
+            w.close().await.unwrap();
+            assert_eq!(w.into_inner(), data);
+
+            let mut r = Base45DecodeReader::new(&b"QED8a"[..]);
+            let err = r.read_to_end(&mut Vec::new()).await.unwrap_err();
+            assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+        });
+    }
+}
@@ -0,0 +1,105 @@
+//! Fixed-width integer encodings for compact numeric IDs.
+//!
+//! Integers are encoded as their big-endian bytes, so every value of a type has the same width
+//! and leading zeros are kept.
+//!
+//! A UUID is a 128-bit big-endian value, so its fixed 24-character form is that of
+//! `id.as_u128()`; the `uuid` feature adds `encode_uuid` and `decode_uuid` for it.
+
+#[cfg(feature = "alloc")]
+use alloc::string::String;
+
+#[cfg(feature = "alloc")]
+use crate::encode_array;
+use crate::{Base45Error, decode_slice, encoded_len};
+
+macro_rules! int_codec {
+    ($ty:ty, $enc:ident, $dec:ident) => {
+        #[doc = concat!("Encode a `", stringify!($ty), "` as exactly `encoded_len(size_of::<", stringify!($ty), ">())` Base45 characters.")]
+        #[cfg(feature = "alloc")]
+        pub fn $enc(value: $ty) -> String {
+            const N: usize = size_of::<$ty>();
+            let out: [u8; encoded_len(N)] = encode_array(&value.to_be_bytes());
+            out.iter().map(|&b| b as char).collect()
+        }
+
+        #[doc = concat!("Decode a `", stringify!($ty), "` produced by [`", stringify!($enc), "`].")]
+        /// Inputs of any other width are rejected with `InvalidLength`.
+        pub fn $dec(s: &str) -> Result<$ty, Base45Error> {
+            const N: usize = size_of::<$ty>();
+            if s.len() != encoded_len(N) {
+                return Err(Base45Error::invalid_length(encoded_len(N), s.len()));
+            }
+            let mut buf = [0u8; N];
+            decode_slice(s, &mut buf)?;
+            Ok(<$ty>::from_be_bytes(buf))
+        }
+    };
+}
+
+int_codec!(u32, encode_u32, decode_u32);
+int_codec!(u64, encode_u64, decode_u64);
+int_codec!(u128, encode_u128, decode_u128);
+
+/// Encode a UUID as exactly 24 Base45 characters, the encoding of its 16 bytes.
+#[cfg(all(feature = "uuid", feature = "alloc"))]
+pub fn encode_uuid(id: uuid::Uuid) -> String {
+    encode_u128(id.as_u128())
+}
+
+/// Decode a UUID produced by [`encode_uuid`].
+/// Inputs of any other width are rejected with `InvalidLength`.
+#[cfg(feature = "uuid")]
+pub fn decode_uuid(s: &str) -> Result<uuid::Uuid, Base45Error> {
+    decode_u128(s).map(uuid::Uuid::from_u128)
+}
+
+#[cfg(all(test, feature = "alloc"))]
+mod tests {
+    use super::*;
+    use crate::ErrorKind;
+
+    #[test]
+    fn fixed_width_roundtrip() {
+        assert_eq!(encode_u32(0), "000000");
+        assert_eq!(encode_u32(u32::MAX), "FGWFGW");
+        assert_eq!(encode_u64(1).len(), 12);
+        assert_eq!(encode_u128(u128::MAX).len(), 24);
+        for v in [0u64, 1, 44, 45, 65535, 65536, u64::MAX / 3, u64::MAX] {
+            assert_eq!(decode_u64(&encode_u64(v)).unwrap(), v);
+        }
+        assert_eq!(decode_u128(&encode_u128(1 << 100)).unwrap(), 1 << 100);
+        // Matches the byte encoding of a UUID's 16 big-endian bytes
+        let uuid = 0x67e5_5044_10b1_426f_9247_bb68_0e5f_e0c8u128;
+        assert_eq!(encode_u128(uuid), crate::encode(uuid.to_be_bytes()));
+    }
+
+    #[cfg(feature = "uuid")]
+    #[test]
+    fn uuid_roundtrip() {
+        let id = uuid::Uuid::from_u128(0x67e5_5044_10b1_426f_9247_bb68_0e5f_e0c8);
+        let s = encode_uuid(id);
+        assert_eq!(s.len(), 24);
+        assert_eq!(s, crate::encode(id.as_bytes()));
+        assert_eq!(decode_uuid(&s).unwrap(), id);
+        assert_eq!(
+            decode_uuid(&s[..21]).unwrap_err().kind,
+            ErrorKind::InvalidLength
+        );
+    }
+
+    #[test]
+    fn rejects_wrong_width() {
+        let err = decode_u32("00000").unwrap_err();
+        assert_eq!(err.kind, ErrorKind::InvalidLength);
+        assert_eq!((err.expected, err.value), (Some(6), Some(5)));
+        assert!(matches!(
+            decode_u32(&encode_u64(7)),
+            Err(e) if e.kind == ErrorKind::InvalidLength
+        ));
+        assert!(matches!(
+            decode_u32("GGW000"),
+            Err(e) if e.kind == ErrorKind::Overflow
+        ));
+    }
+}
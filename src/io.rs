@@ -0,0 +1,960 @@
+//! Streaming into and out of `std::io`.
+//!
+//! Decode failures surface as `io::Error` of kind `InvalidData` wrapping the [`Base45Error`].
+
+use std::io::{self, BufRead, Read, Write};
+
+use crate::{
+    Base45Error, ErrorKind, char_err, char_err_at, decode_exact, decode_tail, decode_triple,
+    decode_vec_in_place, encode_exact, encode_pair, encode_single, encode_slice, encoded_len,
+};
+
+/// Number of 3-character groups processed per chunk.
+const GROUPS_PER_CHUNK: usize = 256;
+
+impl From<Base45Error> for io::Error {
+    fn from(e: Base45Error) -> io::Error {
+        io::Error::new(io::ErrorKind::InvalidData, e)
+    }
+}
+
+/// Encode `input` and write the Base45 ASCII into `w` in fixed-size chunks, without a full output
+/// `String`. Returns the number of characters written.
+pub fn encode_to_writer<W: Write + ?Sized>(input: &[u8], w: &mut W) -> io::Result<usize> {
+    let mut buf = [0u8; GROUPS_PER_CHUNK * 3];
+    let mut written = 0;
+    for chunk in input.chunks(GROUPS_PER_CHUNK * 2) {
+        let n = encode_slice(chunk, &mut buf)?;
+        w.write_all(&buf[..n])?;
+        written += n;
+    }
+    Ok(written)
+}
+
+/// Decode `s` and write the raw bytes into `w` in fixed-size chunks, without a full output `Vec`.
+/// Returns the number of bytes written.
+///
+/// Decoding is checked chunk by chunk, so on error the bytes preceding the failing chunk have
+/// already been written.
+pub fn decode_to_writer<W: Write + ?Sized>(s: &str, w: &mut W) -> io::Result<usize> {
+    let mut buf = [0u8; GROUPS_PER_CHUNK * 2];
+    let mut written = 0;
+    let mut at = 0;
+    for chunk in s.as_bytes().chunks(GROUPS_PER_CHUNK * 3) {
+        let mut groups = chunk.chunks_exact(3);
+        let mut n = 0;
+        for group in &mut groups {
+            buf[n..n + 2]
+                .copy_from_slice(&decode_triple(group, at).map_err(char_err(s.as_bytes()))?);
+            n += 2;
+            at += 3;
+        }
+        let tail = groups.remainder();
+        if !tail.is_empty() {
+            buf[n] = decode_tail(tail, at).map_err(char_err(s.as_bytes()))?;
+            n += 1;
+        }
+        w.write_all(&buf[..n])?;
+        written += n;
+    }
+    Ok(written)
+}
+
+/// Decode each line `r` yields as a separate record, reading one line at a time. Line endings
+/// (`\n` or `\r\n`) are not part of the record, and error positions are offsets in the line.
+///
+/// Decode failures are `io::Error`s of kind `InvalidData` wrapping the [`Base45Error`] and end
+/// only their own record; read failures are passed through.
+///
+/// ```
+/// let log = "QED8WEX0\r\nBB8\n~\n";
+/// let records: Vec<_> = qr_base45::decode_lines(log.as_bytes()).collect();
+/// assert_eq!(records[0].as_ref().unwrap(), b"ietf!");
+/// assert_eq!(records[1].as_ref().unwrap(), b"AB");
+/// assert!(records[2].is_err());
+/// assert_eq!(records.len(), 3);
+/// ```
+pub fn decode_lines<R: BufRead>(mut r: R) -> impl Iterator<Item = io::Result<Vec<u8>>> {
+    core::iter::from_fn(move || {
+        let mut line = Vec::new();
+        match r.read_until(b'\n', &mut line) {
+            Ok(0) => return None,
+            Ok(_) => {}
+            Err(e) => return Some(Err(e)),
+        }
+        if line.last() == Some(&b'\n') {
+            line.pop();
+            if line.last() == Some(&b'\r') {
+                line.pop();
+            }
+        }
+        Some(decode_vec_in_place(line).map_err(io::Error::from))
+    })
+}
+
+/// Encode everything `r` yields into `w`, holding no more than `buf` in memory: a 10 GB file
+/// streams through a 64 KiB buffer. Nothing is allocated. Returns the number of characters
+/// written.
+///
+/// `buf` is split between input and output and must be at least 5 bytes; larger buffers mean
+/// fewer, bigger reads and writes.
+///
+/// ```
+/// let mut out = Vec::new();
+/// let mut buf = [0u8; 4096];
+/// qr_base45::encode_stream(&mut &b"ietf!"[..], &mut out, &mut buf).unwrap();
+/// assert_eq!(out, b"QED8WEX0");
+/// ```
+pub fn encode_stream<R: Read + ?Sized, W: Write + ?Sized>(
+    r: &mut R,
+    w: &mut W,
+    buf: &mut [u8],
+) -> io::Result<u64> {
+    // Every chunk but the last is a whole number of groups
+    let (input, out) = split_buffer(buf, 2, 3)?;
+    let mut written = 0;
+    loop {
+        let n = fill(r, input)?;
+        let len = encoded_len(n);
+        encode_exact(&input[..n], &mut out[..len]);
+        w.write_all(&out[..len])?;
+        written += len as u64;
+        if n < input.len() {
+            return Ok(written);
+        }
+    }
+}
+
+/// Decode the Base45 text `r` yields into `w`, holding no more than `buf` in memory. Nothing is
+/// allocated. Returns the number of bytes written.
+///
+/// `buf` is split between input and output and must be at least 5 bytes. Decoding is checked
+/// chunk by chunk, so on error the bytes preceding the failing chunk have already been written;
+/// the error carries its position in the whole stream.
+///
+/// ```
+/// let mut out = Vec::new();
+/// let mut buf = [0u8; 4096];
+/// qr_base45::decode_stream(&mut &b"QED8WEX0"[..], &mut out, &mut buf).unwrap();
+/// assert_eq!(out, b"ietf!");
+/// ```
+pub fn decode_stream<R: Read + ?Sized, W: Write + ?Sized>(
+    r: &mut R,
+    w: &mut W,
+    buf: &mut [u8],
+) -> io::Result<u64> {
+    let (input, out) = split_buffer(buf, 3, 2)?;
+    let mut written = 0;
+    let mut at = 0;
+    loop {
+        let n = fill(r, input)?;
+        let chunk = &input[..n];
+        let len = n / 3 * 2 + n % 3 / 2;
+        decode_exact(chunk, &mut out[..len]).map_err(|mut e| {
+            e.position += at;
+            char_err_at(chunk, at)(e)
+        })?;
+        w.write_all(&out[..len])?;
+        written += len as u64;
+        at += n;
+        if n < input.len() {
+            return Ok(written);
+        }
+    }
+}
+
+/// An `io::Write` adapter: bytes written to it reach the inner writer Base45-encoded.
+///
+/// An odd trailing byte is held until the next write completes its group, or until
+/// [`finish`](Base45EncodeWriter::finish) writes it as the final 2 characters. Dropping the
+/// adapter also writes it, ignoring errors; call `finish` to see them.
+///
+/// ```
+/// use std::io::Write;
+///
+/// let mut w = qr_base45::Base45EncodeWriter::new(Vec::new());
+/// w.write_all(b"ie")?;
+/// w.write_all(b"tf!")?;
+/// assert_eq!(w.finish()?, b"QED8WEX0");
+/// # Ok::<(), std::io::Error>(())
+/// ```
+#[derive(Debug)]
+pub struct Base45EncodeWriter<W: Write> {
+    // `None` only once `finish` has taken it
+    inner: Option<W>,
+    pending: Option<u8>,
+}
+
+impl<W: Write> Base45EncodeWriter<W> {
+    /// Encode into `inner`.
+    pub fn new(inner: W) -> Self {
+        Base45EncodeWriter {
+            inner: Some(inner),
+            pending: None,
+        }
+    }
+
+    /// The inner writer.
+    pub fn get_ref(&self) -> &W {
+        self.inner
+            .as_ref()
+            .expect("inner writer present until finish")
+    }
+
+    /// The inner writer. Writing to it directly interleaves with the encoded output.
+    pub fn get_mut(&mut self) -> &mut W {
+        self.inner
+            .as_mut()
+            .expect("inner writer present until finish")
+    }
+
+    /// Write the characters of a held odd byte, flush, and return the inner writer.
+    pub fn finish(mut self) -> io::Result<W> {
+        self.write_pending()?;
+        let mut inner = self
+            .inner
+            .take()
+            .expect("inner writer present until finish");
+        inner.flush()?;
+        Ok(inner)
+    }
+
+    fn write_pending(&mut self) -> io::Result<()> {
+        if let Some(byte) = self.pending {
+            self.get_mut().write_all(&encode_single(byte))?;
+            self.pending = None;
+        }
+        Ok(())
+    }
+
+    /// Encode `buf` to the inner writer, counting in `consumed` the bytes taken from it so far.
+    fn write_groups(&mut self, buf: &[u8], consumed: &mut usize) -> io::Result<()> {
+        let mut input = buf;
+        if let (Some(first), [second, rest @ ..]) = (self.pending, input) {
+            let x = (first as u16) * 256 + (*second as u16);
+            self.get_mut().write_all(&encode_pair(x))?;
+            self.pending = None;
+            *consumed = 1;
+            input = rest;
+        }
+        let whole = input.len() / 2 * 2;
+        let mut out = [0u8; GROUPS_PER_CHUNK * 3];
+        for chunk in input[..whole].chunks(GROUPS_PER_CHUNK * 2) {
+            let n = encode_slice(chunk, &mut out)?;
+            self.get_mut().write_all(&out[..n])?;
+            *consumed += chunk.len();
+        }
+        if let [last] = input[whole..] {
+            self.pending = Some(last);
+            *consumed += 1;
+        }
+        Ok(())
+    }
+}
+
+impl<W: Write> Write for Base45EncodeWriter<W> {
+    /// Once part of `buf` has been encoded to the inner writer, a later failure is reported as a
+    /// short write of that part; the error comes back from the next call.
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let mut consumed = 0;
+        match self.write_groups(buf, &mut consumed) {
+            Err(e) if consumed == 0 => Err(e),
+            _ => Ok(consumed),
+        }
+    }
+
+    /// Flush the inner writer. A held odd byte stays held: only `finish` can end the stream.
+    fn flush(&mut self) -> io::Result<()> {
+        self.get_mut().flush()
+    }
+}
+
+impl<W: Write> Drop for Base45EncodeWriter<W> {
+    fn drop(&mut self) {
+        if self.inner.is_some() {
+            let _ = self.write_pending();
+        }
+    }
+}
+
+/// An `io::Write` adapter: takes Base45 text in pieces of any size and writes the decoded bytes
+/// to the inner writer.
+///
+/// A group split across writes is held until it is complete, or until
+/// [`finish`](Base45DecodeWriter::finish) decodes it as the final group. Decode failures are
+/// `io::Error`s of kind `InvalidData` wrapping the [`Base45Error`], positioned in the whole
+/// stream; the writer then keeps returning the same error. Dropping the adapter also decodes a
+/// held group, ignoring errors; call `finish` to see them.
+///
+/// ```
+/// use std::io::Write;
+///
+/// let mut w = qr_base45::Base45DecodeWriter::new(Vec::new());
+/// w.write_all(b"QE")?;
+/// w.write_all(b"D8WE")?;
+/// w.write_all(b"X0")?;
+/// assert_eq!(w.finish()?, b"ietf!");
+/// # Ok::<(), std::io::Error>(())
+/// ```
+#[derive(Debug)]
+pub struct Base45DecodeWriter<W: Write> {
+    // `None` only once `finish` has taken it
+    inner: Option<W>,
+    /// Characters of an incomplete group: `held[..held_len]`.
+    held: [u8; 2],
+    held_len: usize,
+    /// Stream offset of the next character to decode.
+    at: usize,
+    error: Option<Base45Error>,
+}
+
+impl<W: Write> Base45DecodeWriter<W> {
+    /// Decode into `inner`.
+    pub fn new(inner: W) -> Self {
+        Base45DecodeWriter {
+            inner: Some(inner),
+            held: [0; 2],
+            held_len: 0,
+            at: 0,
+            error: None,
+        }
+    }
+
+    /// The inner writer.
+    pub fn get_ref(&self) -> &W {
+        self.inner
+            .as_ref()
+            .expect("inner writer present until finish")
+    }
+
+    /// The inner writer. Writing to it directly interleaves with the decoded output.
+    pub fn get_mut(&mut self) -> &mut W {
+        self.inner
+            .as_mut()
+            .expect("inner writer present until finish")
+    }
+
+    /// Decode a held partial group as the end of the stream, flush, and return the inner writer.
+    pub fn finish(mut self) -> io::Result<W> {
+        self.decode_held()?;
+        let mut inner = self
+            .inner
+            .take()
+            .expect("inner writer present until finish");
+        inner.flush()?;
+        Ok(inner)
+    }
+
+    fn decode_held(&mut self) -> io::Result<()> {
+        let (held, n) = (self.held, self.held_len);
+        self.held_len = 0;
+        self.decode_text(&held[..n])
+    }
+
+    /// Decode `buf` to the inner writer, holding an incomplete final group. A held group is only
+    /// released once its bytes are written.
+    fn write_text(&mut self, buf: &[u8]) -> io::Result<()> {
+        let mut text = buf;
+        if self.held_len > 0 {
+            let need = 3 - self.held_len;
+            let Some((head, rest)) = text.split_at_checked(need) else {
+                self.held[self.held_len..self.held_len + text.len()].copy_from_slice(text);
+                self.held_len += text.len();
+                return Ok(());
+            };
+            let mut group = [0u8; 3];
+            group[..self.held_len].copy_from_slice(&self.held[..self.held_len]);
+            group[self.held_len..].copy_from_slice(head);
+            self.decode_text(&group)?;
+            self.held_len = 0;
+            text = rest;
+        }
+        let whole = text.len() / 3 * 3;
+        self.decode_text(&text[..whole])?;
+        let rest = &text[whole..];
+        self.held[..rest.len()].copy_from_slice(rest);
+        self.held_len = rest.len();
+        Ok(())
+    }
+
+    /// Decode `text`, whole groups or the final tail, and write the bytes to the inner writer.
+    fn decode_text(&mut self, text: &[u8]) -> io::Result<()> {
+        if let Some(e) = self.error {
+            return Err(e.into());
+        }
+        let mut out = [0u8; GROUPS_PER_CHUNK * 2];
+        for chunk in text.chunks(GROUPS_PER_CHUNK * 3) {
+            let len = chunk.len() / 3 * 2 + chunk.len() % 3 / 2;
+            if let Err(mut e) = decode_exact(chunk, &mut out[..len]) {
+                e.position += self.at;
+                let e = char_err_at(chunk, self.at)(e);
+                self.error = Some(e);
+                return Err(e.into());
+            }
+            self.get_mut().write_all(&out[..len])?;
+            self.at += chunk.len();
+        }
+        Ok(())
+    }
+}
+
+impl<W: Write> Write for Base45DecodeWriter<W> {
+    /// Once part of `buf` has been decoded to the inner writer, a later failure is reported as a
+    /// short write of that part; the error comes back from the next call.
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        if let Some(e) = self.error {
+            return Err(e.into());
+        }
+        // `at + held_len` is the stream offset of the next character not yet taken from a caller
+        let start = self.at + self.held_len;
+        match self.write_text(buf) {
+            Ok(()) => Ok(buf.len()),
+            Err(e) => match self.at + self.held_len - start {
+                0 => Err(e),
+                consumed => Ok(consumed),
+            },
+        }
+    }
+
+    /// Flush the inner writer. A held partial group stays held: only `finish` can end the stream.
+    fn flush(&mut self) -> io::Result<()> {
+        self.get_mut().flush()
+    }
+}
+
+impl<W: Write> Drop for Base45DecodeWriter<W> {
+    fn drop(&mut self) {
+        if self.inner.is_some() {
+            let _ = self.decode_held();
+        }
+    }
+}
+
+/// An `io::Read` adapter: reads Base45 text from the inner reader and yields the decoded bytes.
+///
+/// A group split across reads of the inner reader is held until it is complete. Decode failures
+/// are `io::Error`s of kind `InvalidData` wrapping the [`Base45Error`], positioned in the whole
+/// stream; the reader then keeps returning the same error.
+///
+/// ```
+/// use std::io::Read;
+///
+/// let mut r = qr_base45::Base45DecodeReader::new(&b"QED8WEX0"[..]);
+/// let mut out = Vec::new();
+/// r.read_to_end(&mut out)?;
+/// assert_eq!(out, b"ietf!");
+/// # Ok::<(), std::io::Error>(())
+/// ```
+#[derive(Debug)]
+pub struct Base45DecodeReader<R: Read> {
+    inner: R,
+    /// Characters read but not yet decoded: `text[..text_len]`.
+    text: [u8; GROUPS_PER_CHUNK * 3],
+    text_len: usize,
+    /// Decoded bytes not yet returned: `bytes[pos..len]`.
+    bytes: [u8; GROUPS_PER_CHUNK * 2],
+    pos: usize,
+    len: usize,
+    /// Stream offset of `text[0]`.
+    at: usize,
+    eof: bool,
+    error: Option<Base45Error>,
+}
+
+impl<R: Read> Base45DecodeReader<R> {
+    /// Decode the text read from `inner`.
+    pub fn new(inner: R) -> Self {
+        Base45DecodeReader {
+            inner,
+            text: [0; GROUPS_PER_CHUNK * 3],
+            text_len: 0,
+            bytes: [0; GROUPS_PER_CHUNK * 2],
+            pos: 0,
+            len: 0,
+            at: 0,
+            eof: false,
+            error: None,
+        }
+    }
+
+    /// The inner reader.
+    pub fn get_ref(&self) -> &R {
+        &self.inner
+    }
+
+    /// The inner reader. Reading from it directly skips that text.
+    pub fn get_mut(&mut self) -> &mut R {
+        &mut self.inner
+    }
+
+    /// Unwrap the inner reader, dropping any buffered text and bytes.
+    pub fn into_inner(self) -> R {
+        self.inner
+    }
+
+    /// Decode more bytes into the empty output buffer; leaves it empty only at the end.
+    fn refill(&mut self) -> io::Result<()> {
+        loop {
+            if let Some(e) = self.error {
+                return Err(e.into());
+            }
+            let whole = self.text_len / 3 * 3;
+            if whole > 0 || (self.eof && self.text_len > 0) {
+                let n = if whole > 0 { whole } else { self.text_len };
+                let text = &self.text[..n];
+                let len = n / 3 * 2 + n % 3 / 2;
+                if let Err(mut e) = decode_exact(text, &mut self.bytes[..len]) {
+                    e.position += self.at;
+                    let e = char_err_at(text, self.at)(e);
+                    self.error = Some(e);
+                    return Err(e.into());
+                }
+                (self.pos, self.len) = (0, len);
+                self.text.copy_within(n..self.text_len, 0);
+                self.text_len -= n;
+                self.at += n;
+                return Ok(());
+            }
+            if self.eof {
+                return Ok(());
+            }
+            match self.inner.read(&mut self.text[self.text_len..]) {
+                Ok(0) => self.eof = true,
+                Ok(n) => self.text_len += n,
+                Err(e) if e.kind() == io::ErrorKind::Interrupted => {}
+                Err(e) => return Err(e),
+            }
+        }
+    }
+}
+
+impl<R: Read> Read for Base45DecodeReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        if buf.is_empty() {
+            return Ok(0);
+        }
+        if self.pos == self.len {
+            self.refill()?;
+        }
+        let n = buf.len().min(self.len - self.pos);
+        buf[..n].copy_from_slice(&self.bytes[self.pos..self.pos + n]);
+        self.pos += n;
+        Ok(n)
+    }
+}
+
+/// An `io::Read` adapter: reads raw bytes from the inner reader and yields their Base45
+/// encoding, so the text never has to be held whole.
+///
+/// ```
+/// use std::io::Read;
+///
+/// let mut r = qr_base45::Base45EncodeReader::new(&b"ietf!"[..]);
+/// let mut text = String::new();
+/// r.read_to_string(&mut text)?;
+/// assert_eq!(text, "QED8WEX0");
+/// # Ok::<(), std::io::Error>(())
+/// ```
+#[derive(Debug)]
+pub struct Base45EncodeReader<R: Read> {
+    inner: R,
+    /// Bytes read but not yet encoded: `input[..input_len]`.
+    input: [u8; GROUPS_PER_CHUNK * 2],
+    input_len: usize,
+    /// Characters not yet returned: `text[pos..len]`.
+    text: [u8; GROUPS_PER_CHUNK * 3],
+    pos: usize,
+    len: usize,
+    eof: bool,
+}
+
+impl<R: Read> Base45EncodeReader<R> {
+    /// Encode the bytes read from `inner`.
+    pub fn new(inner: R) -> Self {
+        Base45EncodeReader {
+            inner,
+            input: [0; GROUPS_PER_CHUNK * 2],
+            input_len: 0,
+            text: [0; GROUPS_PER_CHUNK * 3],
+            pos: 0,
+            len: 0,
+            eof: false,
+        }
+    }
+
+    /// The inner reader.
+    pub fn get_ref(&self) -> &R {
+        &self.inner
+    }
+
+    /// The inner reader. Reading from it directly skips those bytes.
+    pub fn get_mut(&mut self) -> &mut R {
+        &mut self.inner
+    }
+
+    /// Unwrap the inner reader, dropping any buffered bytes and text.
+    pub fn into_inner(self) -> R {
+        self.inner
+    }
+
+    /// Encode more characters into the empty text buffer; leaves it empty only at the end.
+    fn refill(&mut self) -> io::Result<()> {
+        loop {
+            // Whole groups only, until the end of input settles the last byte
+            let n = match self.input_len {
+                0 if self.eof => return Ok(()),
+                1 if self.eof => 1,
+                n => n / 2 * 2,
+            };
+            if n > 0 {
+                let len = encoded_len(n);
+                encode_exact(&self.input[..n], &mut self.text[..len]);
+                (self.pos, self.len) = (0, len);
+                self.input.copy_within(n..self.input_len, 0);
+                self.input_len -= n;
+                return Ok(());
+            }
+            match self.inner.read(&mut self.input[self.input_len..]) {
+                Ok(0) => self.eof = true,
+                Ok(n) => self.input_len += n,
+                Err(e) if e.kind() == io::ErrorKind::Interrupted => {}
+                Err(e) => return Err(e),
+            }
+        }
+    }
+}
+
+impl<R: Read> Read for Base45EncodeReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        if buf.is_empty() {
+            return Ok(0);
+        }
+        if self.pos == self.len {
+            self.refill()?;
+        }
+        let n = buf.len().min(self.len - self.pos);
+        buf[..n].copy_from_slice(&self.text[self.pos..self.pos + n]);
+        self.pos += n;
+        Ok(n)
+    }
+}
+
+/// Split `buf` into an input area of whole `unit`-sized groups and an output area for their
+/// `out_unit`-sized results.
+fn split_buffer(
+    buf: &mut [u8],
+    unit: usize,
+    out_unit: usize,
+) -> io::Result<(&mut [u8], &mut [u8])> {
+    let groups = buf.len() / (unit + out_unit);
+    if groups == 0 {
+        let e = Base45Error::new(ErrorKind::BufferTooSmall);
+        return Err(io::Error::new(io::ErrorKind::InvalidInput, e));
+    }
+    let (input, out) = buf.split_at_mut(groups * unit);
+    Ok((input, &mut out[..groups * out_unit]))
+}
+
+/// Read from `r` until `buf` is full or the input ends. Returns the number of bytes read.
+fn fill<R: Read + ?Sized>(r: &mut R, buf: &mut [u8]) -> io::Result<usize> {
+    let mut n = 0;
+    while n < buf.len() {
+        match r.read(&mut buf[n..]) {
+            Ok(0) => break,
+            Ok(k) => n += k,
+            Err(e) if e.kind() == io::ErrorKind::Interrupted => {}
+            Err(e) => return Err(e),
+        }
+    }
+    Ok(n)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ErrorKind;
+
+    /// Takes every write whole, except that write number `fail_at` fails.
+    struct Flaky {
+        out: Vec<u8>,
+        writes: usize,
+        fail_at: usize,
+    }
+
+    impl Flaky {
+        fn new(fail_at: usize) -> Self {
+            Flaky {
+                out: Vec::new(),
+                writes: 0,
+                fail_at,
+            }
+        }
+    }
+
+    impl Write for Flaky {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            self.writes += 1;
+            if self.writes == self.fail_at {
+                return Err(io::Error::other("flaky"));
+            }
+            self.out.extend_from_slice(buf);
+            Ok(buf.len())
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn encode_to_writer_streams() {
+        let data: Vec<u8> = (0..=255).cycle().take(5001).collect();
+        let mut sink = Vec::new();
+        let n = encode_to_writer(&data, &mut sink).unwrap();
+        assert_eq!(n, crate::encoded_len(data.len()));
+        assert_eq!(sink, crate::encode(&data).into_bytes());
+    }
+
+    #[test]
+    fn decode_to_writer_streams() {
+        let data: Vec<u8> = (0..=255).cycle().take(5000).collect();
+        let s = crate::encode(&data);
+        let mut sink = Vec::new();
+        assert_eq!(decode_to_writer(&s, &mut sink).unwrap(), data.len());
+        assert_eq!(sink, data);
+
+        let err = decode_to_writer("BB8ZZ", &mut Vec::new()).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+        let inner = err.into_inner().unwrap().downcast::<Base45Error>().unwrap();
+        assert_eq!(inner.kind, ErrorKind::TailOverflow);
+    }
+
+    #[test]
+    fn streams_match_one_shot_calls() {
+        let data: Vec<u8> = (0..10_001u32).map(|i| (i * 167 + 13) as u8).collect();
+        let text = crate::encode(&data);
+        for size in [5, 6, 11, 100, 4096, 1 << 16] {
+            let mut buf = vec![0; size];
+            // One byte per read, so every chunk is assembled from short reads
+            let mut out = Vec::new();
+            let mut slow = io::BufReader::with_capacity(1, &data[..]);
+            let n = encode_stream(&mut slow, &mut out, &mut buf).unwrap();
+            assert_eq!((n, &out), (text.len() as u64, &text.clone().into_bytes()));
+
+            let mut back = Vec::new();
+            let n = decode_stream(&mut text.as_bytes(), &mut back, &mut buf).unwrap();
+            assert_eq!((n, back), (data.len() as u64, data.clone()));
+        }
+        let err = encode_stream(&mut &data[..], &mut Vec::new(), &mut [0; 4]).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidInput);
+    }
+
+    #[test]
+    fn decode_stream_reports_stream_positions() {
+        let mut text = crate::encode([0x5A; 1000]).into_bytes();
+        text[1234] = b'a';
+        let mut buf = [0u8; 100];
+        let err = decode_stream(&mut &text[..], &mut Vec::new(), &mut buf).unwrap_err();
+        let inner = err.into_inner().unwrap().downcast::<Base45Error>().unwrap();
+        assert_eq!((inner.kind, inner.position), (ErrorKind::InvalidChar, 1234));
+        assert_eq!(inner.ch(), Some('a'));
+
+        let err = decode_stream(&mut &b"BB8BB8A"[..], &mut Vec::new(), &mut buf).unwrap_err();
+        let inner = err.into_inner().unwrap().downcast::<Base45Error>().unwrap();
+        assert_eq!((inner.kind, inner.position), (ErrorKind::Dangling, 6));
+    }
+
+    #[test]
+    fn encode_writer_matches_encode() {
+        let data: Vec<u8> = (0..3001u32).map(|i| (i * 167 + 13) as u8).collect();
+        for piece in [1, 2, 3, 7, 1000, 4000] {
+            let mut w = Base45EncodeWriter::new(Vec::new());
+            for chunk in data.chunks(piece) {
+                w.write_all(chunk).unwrap();
+            }
+            assert_eq!(w.finish().unwrap(), crate::encode(&data).into_bytes());
+        }
+        // Dropping writes the held byte too
+        let mut out = Vec::new();
+        {
+            let mut w = Base45EncodeWriter::new(&mut out);
+            w.write_all(b"ietf!").unwrap();
+            w.flush().unwrap();
+            assert_eq!(w.get_ref().len(), 6);
+        }
+        assert_eq!(out, b"QED8WEX0");
+    }
+
+    #[test]
+    fn encode_writer_reports_committed_input() {
+        let data: Vec<u8> = (0..3001u32).map(|i| (i * 167 + 13) as u8).collect();
+        // The second chunk fails after the first went out: a short write, then a clean retry
+        let mut w = Base45EncodeWriter::new(Flaky::new(2));
+        let n = w.write(&data).unwrap();
+        assert_eq!(n, GROUPS_PER_CHUNK * 2);
+        w.write_all(&data[n..]).unwrap();
+        assert_eq!(w.finish().unwrap().out, crate::encode(&data).into_bytes());
+
+        // Nothing went out: the error, with the odd byte still held
+        let mut w = Base45EncodeWriter::new(Flaky::new(1));
+        assert_eq!(w.write(b"i").unwrap(), 1);
+        assert!(w.write(b"etf!").is_err());
+        w.write_all(b"etf!").unwrap();
+        assert_eq!(w.finish().unwrap().out, b"QED8WEX0");
+    }
+
+    #[test]
+    fn decode_reader_matches_decode() {
+        let data: Vec<u8> = (0..3001u32).map(|i| (i * 167 + 13) as u8).collect();
+        let text = crate::encode(&data);
+        for piece in [1, 2, 4, 1000] {
+            // Short reads of the text split groups at every offset
+            let inner = io::BufReader::with_capacity(piece, text.as_bytes());
+            let mut out = Vec::new();
+            let mut r = Base45DecodeReader::new(inner);
+            let mut buf = vec![0; piece + 1];
+            loop {
+                match r.read(&mut buf).unwrap() {
+                    0 => break,
+                    n => out.extend_from_slice(&buf[..n]),
+                }
+            }
+            assert_eq!(out, data);
+        }
+    }
+
+    #[test]
+    fn decode_reader_errors_are_sticky() {
+        let mut text = crate::encode([0x5A; 1000]).into_bytes();
+        text[1000] = b'a';
+        let mut r = Base45DecodeReader::new(&text[..]);
+        let mut out = Vec::new();
+        let err = r.read_to_end(&mut out).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+        let inner = err.into_inner().unwrap().downcast::<Base45Error>().unwrap();
+        assert_eq!((inner.kind, inner.position), (ErrorKind::InvalidChar, 1000));
+        assert!(out.len() <= 666);
+        assert!(r.read(&mut [0; 8]).is_err());
+
+        let err = Base45DecodeReader::new(&b"BB8A"[..])
+            .read_to_end(&mut Vec::new())
+            .unwrap_err();
+        let inner = err.into_inner().unwrap().downcast::<Base45Error>().unwrap();
+        assert_eq!((inner.kind, inner.position), (ErrorKind::Dangling, 3));
+    }
+
+    #[test]
+    fn encode_reader_matches_encode() {
+        let data: Vec<u8> = (0..3001u32).map(|i| (i * 167 + 13) as u8).collect();
+        for piece in [1, 2, 3, 1000] {
+            // Short reads of the input split groups at every offset
+            let inner = io::BufReader::with_capacity(piece, &data[..]);
+            let mut r = Base45EncodeReader::new(inner);
+            let mut text = Vec::new();
+            let mut buf = vec![0; piece + 1];
+            loop {
+                match r.read(&mut buf).unwrap() {
+                    0 => break,
+                    n => text.extend_from_slice(&buf[..n]),
+                }
+            }
+            assert_eq!(text, crate::encode(&data).into_bytes());
+        }
+        // Round trip through both readers
+        let mut back = Vec::new();
+        Base45DecodeReader::new(Base45EncodeReader::new(&data[..]))
+            .read_to_end(&mut back)
+            .unwrap();
+        assert_eq!(back, data);
+    }
+
+    #[test]
+    fn decode_writer_matches_decode() {
+        let data: Vec<u8> = (0..3001u32).map(|i| (i * 167 + 13) as u8).collect();
+        let text = crate::encode(&data);
+        for piece in [1, 2, 4, 1000, 5000] {
+            let mut w = Base45DecodeWriter::new(Vec::new());
+            for chunk in text.as_bytes().chunks(piece) {
+                w.write_all(chunk).unwrap();
+            }
+            assert_eq!(w.finish().unwrap(), data);
+        }
+        // Dropping decodes the held tail too
+        let mut out = Vec::new();
+        {
+            let mut w = Base45DecodeWriter::new(&mut out);
+            w.write_all(b"QED8WEX0").unwrap();
+            w.flush().unwrap();
+            assert_eq!(w.get_ref().len(), 4);
+        }
+        assert_eq!(out, b"ietf!");
+    }
+
+    #[test]
+    fn decode_writer_reports_committed_input() {
+        let data: Vec<u8> = (0..3001u32).map(|i| (i * 167 + 13) as u8).collect();
+        let text = crate::encode(&data).into_bytes();
+        // The second chunk fails after the first went out: a short write, then a clean retry
+        let mut w = Base45DecodeWriter::new(Flaky::new(2));
+        let n = w.write(&text).unwrap();
+        assert_eq!(n, GROUPS_PER_CHUNK * 3);
+        w.write_all(&text[n..]).unwrap();
+        assert_eq!(w.finish().unwrap().out, data);
+
+        // A held group that fails to go out stays held
+        let mut w = Base45DecodeWriter::new(Flaky::new(1));
+        assert_eq!(w.write(b"QE").unwrap(), 2);
+        assert!(w.write(b"D8WEX0").is_err());
+        w.write_all(b"D8WEX0").unwrap();
+        assert_eq!(w.finish().unwrap().out, b"ietf!");
+
+        // A decode error after output went out surfaces on the next call
+        let mut bad = crate::encode([0x5A; 1000]).into_bytes();
+        bad[900] = b'a';
+        let mut w = Base45DecodeWriter::new(Vec::new());
+        assert_eq!(w.write(&bad).unwrap(), GROUPS_PER_CHUNK * 3);
+        let err = w.write(&bad[GROUPS_PER_CHUNK * 3..]).unwrap_err();
+        let inner = err.into_inner().unwrap().downcast::<Base45Error>().unwrap();
+        assert_eq!((inner.kind, inner.position), (ErrorKind::InvalidChar, 900));
+    }
+
+    #[test]
+    fn decode_writer_errors_are_sticky() {
+        let mut text = crate::encode([0x5A; 1000]).into_bytes();
+        text[1000] = b'a';
+        let mut w = Base45DecodeWriter::new(Vec::new());
+        // The bad character lands in a group split across writes
+        let err = w
+            .write_all(&text[..999])
+            .and_then(|()| w.write_all(&text[999..]));
+        let inner = err.unwrap_err().into_inner().unwrap();
+        let inner = inner.downcast::<Base45Error>().unwrap();
+        assert_eq!((inner.kind, inner.position), (ErrorKind::InvalidChar, 1000));
+        assert!(w.write(b"BB8").is_err());
+        assert!(w.finish().is_err());
+
+        // The trailing group is only checked at the end
+        let mut w = Base45DecodeWriter::new(Vec::new());
+        w.write_all(b"BB8A").unwrap();
+        w.flush().unwrap();
+        let err = w.finish().unwrap_err();
+        let inner = err.into_inner().unwrap().downcast::<Base45Error>().unwrap();
+        assert_eq!((inner.kind, inner.position), (ErrorKind::Dangling, 3));
+    }
+
+    #[test]
+    fn decode_lines_yields_one_record_per_line() {
+        let log = b"QED8WEX0\n\r\nBB8Q\nBB8\xFF\r\n%69 VD92EX0";
+        let records: Vec<_> = decode_lines(&log[..]).collect();
+        assert_eq!(records.len(), 5);
+        assert_eq!(records[0].as_ref().unwrap(), b"ietf!");
+        assert_eq!(records[1].as_ref().unwrap(), b"");
+        let inner = |i: usize| {
+            let e = records[i].as_ref().unwrap_err();
+            *e.get_ref().unwrap().downcast_ref::<Base45Error>().unwrap()
+        };
+        assert_eq!((inner(2).kind, inner(2).position), (ErrorKind::Dangling, 3));
+        assert_eq!(inner(3).position, 3);
+        assert_eq!(records[4].as_ref().unwrap(), b"Hello!!");
+    }
+}
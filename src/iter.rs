@@ -0,0 +1,185 @@
+//! Lazy, allocation-free iterator adapters.
+
+use crate::{Base45Error, b45_val, decode_tail, decode_triple, encode_pair, encode_single};
+
+/// Iterator over the Base45 characters of a byte slice, produced on demand.
+/// Created by [`encode_iter`].
+#[derive(Debug, Clone)]
+pub struct EncodeIter<'a> {
+    input: &'a [u8],
+    group: [u8; 3],
+    pos: usize,
+    len: usize,
+}
+
+/// Lazily encode `input`, yielding one Base45 character at a time.
+pub fn encode_iter(input: &[u8]) -> EncodeIter<'_> {
+    EncodeIter {
+        input,
+        group: [0; 3],
+        pos: 0,
+        len: 0,
+    }
+}
+
+impl<'a> EncodeIter<'a> {
+    /// Like [`encode_iter`], but first yields the `len` characters already in `group`.
+    pub(crate) fn with_head(group: [u8; 3], len: usize, input: &'a [u8]) -> EncodeIter<'a> {
+        EncodeIter {
+            input,
+            group,
+            pos: 0,
+            len,
+        }
+    }
+}
+
+impl Iterator for EncodeIter<'_> {
+    type Item = char;
+
+    fn next(&mut self) -> Option<char> {
+        if self.pos == self.len {
+            match *self.input {
+                [u, v, ref rest @ ..] => {
+                    self.group = encode_pair((u as u16) * 256 + (v as u16));
+                    self.len = 3;
+                    self.input = rest;
+                }
+                [u] => {
+                    let [b, a] = encode_single(u);
+                    self.group = [b, a, 0];
+                    self.len = 2;
+                    self.input = &[];
+                }
+                [] => return None,
+            }
+            self.pos = 0;
+        }
+        let ch = self.group[self.pos] as char;
+        self.pos += 1;
+        Some(ch)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let n = self.len - self.pos + crate::encoded_len(self.input.len());
+        (n, Some(n))
+    }
+}
+
+impl ExactSizeIterator for EncodeIter<'_> {}
+
+impl core::iter::FusedIterator for EncodeIter<'_> {}
+
+/// Iterator that decodes Base45 characters pulled from another iterator.
+/// Created by [`decode_iter`].
+///
+/// Yields each decoded byte as soon as its group is complete. The first error ends iteration.
+/// Error positions are byte offsets into the UTF-8 encoding of the characters consumed.
+#[derive(Debug, Clone)]
+pub struct DecodeIter<I> {
+    chars: I,
+    pending: Option<u8>,
+    offset: usize,
+    done: bool,
+}
+
+/// Incrementally decode characters from any `char` source, without collecting them first.
+pub fn decode_iter<I: IntoIterator<Item = char>>(chars: I) -> DecodeIter<I::IntoIter> {
+    DecodeIter {
+        chars: chars.into_iter(),
+        pending: None,
+        offset: 0,
+        done: false,
+    }
+}
+
+impl<I: Iterator<Item = char>> DecodeIter<I> {
+    fn next_group(&mut self) -> Option<Result<u8, Base45Error>> {
+        let mut group = [0u8; 3];
+        let mut n = 0;
+        while n < 3 {
+            let Some(ch) = self.chars.next() else { break };
+            // Validate as each char arrives so the earliest bad char is the one reported
+            match u8::try_from(ch).ok().filter(|&b| b45_val(b).is_some()) {
+                Some(b) => group[n] = b,
+                None => {
+                    let index = self.offset;
+                    return Some(Err(Base45Error::invalid_char(ch, index)));
+                }
+            }
+            // Positions are UTF-8 byte offsets, as for the `&str` decoders
+            self.offset += ch.len_utf8();
+            n += 1;
+        }
+        // Every accepted char is ASCII, so the group started `n` bytes back
+        let start = self.offset - n;
+        match n {
+            0 => None,
+            3 => Some(decode_triple(&group, start).map(|[hi, lo]| {
+                self.pending = Some(lo);
+                hi
+            })),
+            _ => Some(decode_tail(&group[..n], start)),
+        }
+    }
+}
+
+impl<I: Iterator<Item = char>> Iterator for DecodeIter<I> {
+    type Item = Result<u8, Base45Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if let Some(b) = self.pending.take() {
+            return Some(Ok(b));
+        }
+        if self.done {
+            return None;
+        }
+        let item = self.next_group();
+        if !matches!(item, Some(Ok(_))) {
+            self.done = true;
+        }
+        item
+    }
+}
+
+impl<I: Iterator<Item = char>> core::iter::FusedIterator for DecodeIter<I> {}
+
+#[cfg(all(test, feature = "alloc"))]
+mod tests {
+    use super::*;
+    use crate::ErrorKind;
+
+    #[test]
+    fn encode_iter_matches_encode() {
+        for case in [&b""[..], b"A", b"AB", b"ietf!", b"Hello!!", &[0xFF; 7]] {
+            let it = encode_iter(case);
+            assert_eq!(it.len(), crate::encoded_len(case.len()));
+            assert_eq!(it.collect::<String>(), crate::encode(case));
+        }
+        let mut it = encode_iter(b"AB");
+        assert_eq!(it.next(), Some('B'));
+        assert_eq!(it.len(), 2);
+    }
+
+    #[test]
+    fn decode_iter_streams_bytes() {
+        let bytes: Result<Vec<u8>, _> = decode_iter("%69 VD92EX0".chars()).collect();
+        assert_eq!(bytes.unwrap(), b"Hello!!");
+
+        // Bytes before the failing group are still delivered, then iteration stops
+        let mut it = decode_iter("BB8ZZ".chars());
+        assert_eq!(it.next().unwrap().unwrap(), b'A');
+        assert_eq!(it.next().unwrap().unwrap(), b'B');
+        assert!(matches!(it.next(), Some(Err(e)) if e.kind == ErrorKind::TailOverflow));
+        assert!(it.next().is_none());
+
+        assert!(matches!(
+            decode_iter("BB8A".chars()).last(),
+            Some(Err(e)) if e.kind == ErrorKind::Dangling
+        ));
+        assert!(matches!(
+            decode_iter("B😀8".chars()).next(),
+            Some(Err(e)) if e.kind == ErrorKind::InvalidChar
+        ));
+    }
+}
@@ -2,105 +2,883 @@
 //! - Encoding groups: 2 bytes -> 3 chars; 1 byte -> 2 chars.
 //! - Alphabet: "0123456789ABCDEFGHIJKLMNOPQRSTUVWXYZ $%*+-./:"
 //! - Public API encodes &[u8] -> String and decodes &str -> Vec<u8>.
+//! - `no_std` + `alloc` when the default `std` feature is disabled, and `no_std` without any
+//!   allocation when `alloc` is disabled too: the slice, array and iterator APIs remain.
 
-#[derive(Debug, thiserror::Error)]
-pub enum Base45Error {
-    #[error("invalid base45 character")]
-    InvalidChar,
-    #[error("dangling character group")]
-    Dangling,
-    #[error("value overflow")]
-    Overflow,
+#![cfg_attr(not(any(feature = "std", test)), no_std)]
+#![cfg_attr(feature = "portable-simd", feature(portable_simd))]
+
+#[cfg(feature = "alloc")]
+extern crate alloc;
+
+#[cfg(feature = "alloc")]
+pub mod armor;
+#[cfg(feature = "bytes")]
+mod buf;
+#[cfg(feature = "alloc")]
+mod check;
+#[cfg(feature = "alloc")]
+mod codec;
+#[cfg(any(feature = "sqlx", feature = "diesel"))]
+mod db;
+#[cfg(feature = "alloc")]
+mod decoded;
+mod display;
+mod encoding;
+mod error;
+#[cfg(any(feature = "arbitrary", feature = "testing"))]
+mod fault;
+#[cfg(any(feature = "heapless", feature = "arrayvec"))]
+mod fixed;
+pub mod frame;
+#[cfg(feature = "futures")]
+pub mod futures;
+#[cfg(feature = "futures-io")]
+pub mod futures_io;
+mod int;
+#[cfg(feature = "std")]
+mod io;
+mod iter;
+#[cfg(feature = "alloc")]
+mod lossy;
+#[cfg(feature = "alloc")]
+mod options;
+#[cfg(feature = "rayon")]
+mod parallel;
+#[cfg(feature = "alloc")]
+mod prefix;
+#[cfg(any(feature = "tokio", feature = "futures-io"))]
+mod pump;
+pub mod qr;
+#[cfg(feature = "alloc")]
+mod records;
+#[cfg(feature = "schemars")]
+mod schema;
+#[cfg(feature = "alloc")]
+mod scratch;
+#[cfg(feature = "serde")]
+pub mod serde;
+mod simd;
+mod stream;
+#[cfg(feature = "alloc")]
+mod string;
+mod swar;
+#[cfg(feature = "testing")]
+pub mod testing;
+#[cfg(feature = "tokio")]
+pub mod tokio;
+#[cfg(feature = "alloc")]
+mod validate;
+
+#[cfg(feature = "bytes")]
+pub use buf::{decode_to_bytes, decode_to_bytes_mut, encode_buf, encode_to_bytes_mut};
+#[cfg(feature = "alloc")]
+pub use check::{decode_check, encode_check};
+#[cfg(feature = "alloc")]
+pub use codec::{Base45, Codec};
+#[cfg(feature = "alloc")]
+pub use decoded::Base45Bytes;
+pub use display::{Base45Display, display, encode_fmt, encode_with};
+pub use encoding::{DigitOrder, Encoding};
+pub use error::{Base45Error, DecodeError, ErrorKind};
+#[cfg(any(feature = "arbitrary", feature = "testing"))]
+pub use fault::AlmostValid;
+#[cfg(feature = "arrayvec")]
+pub use fixed::{decode_to_array_vec, encode_to_array_string};
+#[cfg(feature = "heapless")]
+pub use fixed::{decode_to_heapless, encode_to_heapless};
+#[cfg(feature = "uuid")]
+pub use int::decode_uuid;
+#[cfg(all(feature = "uuid", feature = "alloc"))]
+pub use int::encode_uuid;
+pub use int::{decode_u32, decode_u64, decode_u128};
+#[cfg(feature = "alloc")]
+pub use int::{encode_u32, encode_u64, encode_u128};
+#[cfg(feature = "std")]
+pub use io::{
+    Base45DecodeReader, Base45DecodeWriter, Base45EncodeReader, Base45EncodeWriter, decode_lines,
+    decode_stream, decode_to_writer, encode_stream, encode_to_writer,
+};
+pub use iter::{DecodeIter, EncodeIter, decode_iter, encode_iter};
+#[cfg(feature = "alloc")]
+pub use lossy::{LossyDecoded, decode_lossy};
+#[cfg(feature = "alloc")]
+pub use options::{DecodeOptions, EncodeOptions, decode_lenient};
+#[cfg(feature = "rayon")]
+pub use parallel::{decode_parallel, encode_parallel};
+#[cfg(feature = "alloc")]
+pub use prefix::{decode_with_prefix, encode_with_prefix};
+#[cfg(feature = "alloc")]
+pub use records::{decode_records, encode_records};
+#[cfg(feature = "alloc")]
+pub use scratch::Base45Scratch;
+pub use stream::{StreamBytes, StreamDecoder, StreamEncoder};
+#[cfg(feature = "alloc")]
+pub use string::Base45String;
+#[cfg(feature = "alloc")]
+pub use validate::{ValidationReport, validate};
+
+#[cfg(feature = "alloc")]
+use alloc::string::String;
+#[cfg(feature = "alloc")]
+use alloc::vec::Vec;
+use core::mem::MaybeUninit;
+
+/// `?` for `const fn`, where the `Try` trait cannot be used yet.
+macro_rules! const_try {
+    ($e:expr) => {
+        match $e {
+            Ok(v) => v,
+            Err(e) => return Err(e),
+        }
+    };
+}
+
+/// Run `$body` as a closure; with the `no-panic` feature, fail to link unless the optimizer has
+/// removed every panic path from it. Only meaningful in optimized builds.
+macro_rules! no_panic {
+    ($body:block) => {{
+        #[cfg(feature = "no-panic")]
+        let guard = $crate::NoPanic;
+        #[allow(clippy::redundant_closure_call)]
+        let out = (move || $body)();
+        #[cfg(feature = "no-panic")]
+        core::mem::forget(guard);
+        out
+    }};
+}
+
+/// Dropped only while unwinding out of a [`no_panic!`] body, which pulls in a symbol that does
+/// not exist.
+#[cfg(feature = "no-panic")]
+struct NoPanic;
+
+#[cfg(feature = "no-panic")]
+impl Drop for NoPanic {
+    fn drop(&mut self) {
+        unsafe extern "C" {
+            #[link_name = "\n\nqr_base45: a function checked by the `no-panic` feature may panic\n\n"]
+            fn may_panic() -> !;
+        }
+        // SAFETY: never called in a build that links
+        unsafe { may_panic() }
+    }
 }
 
 /// Base45 alphabet as per RFC 9285
 pub const BASE45_ALPHABET: &[u8; 45] = b"0123456789ABCDEFGHIJKLMNOPQRSTUVWXYZ $%*+-./:";
 
+/// Entry of [`DECODE_TABLE`] for bytes outside the alphabet.
+pub const INVALID_DIGIT: u8 = 0xFF;
+
+/// Digit value of each byte under [`BASE45_ALPHABET`], indexed by the byte, or [`INVALID_DIGIT`].
+/// Generated at compile time, for custom fast paths that decode a byte per lookup.
+pub const DECODE_TABLE: [u8; 256] = {
+    let mut table = [INVALID_DIGIT; 256];
+    let mut i = 0;
+    while i < BASE45_ALPHABET.len() {
+        table[BASE45_ALPHABET[i] as usize] = i as u8;
+        i += 1;
+    }
+    table
+};
+
 #[inline]
-fn b45_val(ch: u8) -> Option<u16> {
-    match ch {
-        b'0'..=b'9' => Some((ch - b'0') as u16),
-        b'A'..=b'Z' => Some(10 + (ch - b'A') as u16),
-        b' ' => Some(36),
-        b'$' => Some(37),
-        b'%' => Some(38),
-        b'*' => Some(39),
-        b'+' => Some(40),
-        b'-' => Some(41),
-        b'.' => Some(42),
-        b'/' => Some(43),
-        b':' => Some(44),
-        _ => None,
+pub(crate) const fn b45_val(ch: u8) -> Option<u16> {
+    match DECODE_TABLE[ch as usize] {
+        INVALID_DIGIT => None,
+        d => Some(d as u16),
     }
 }
 
-/// Encode arbitrary bytes into a Base45 string.
-/// Groups of 2 bytes produce 3 characters; a final single byte produces 2 characters.
-pub fn encode(input: &[u8]) -> String {
-    let mut out = String::with_capacity((input.len() * 3).div_ceil(2));
+/// [`BASE45_ALPHABET`] padded to 64 entries, so a digit masked to 6 bits needs no bounds check.
+pub(crate) const ALPHABET_64: [u8; 64] = {
+    let mut table = [0; 64];
     let mut i = 0;
-    while i + 1 < input.len() {
-        let x = (input[i] as u16) * 256 + (input[i + 1] as u16);
-        let c = x % 45; // least significant digit
-        let x = x / 45;
-        let b = x % 45;
-        let a = x / 45; // most significant digit (0..=8)
-        // Base45 outputs least-significant digit first
-        out.push(BASE45_ALPHABET[c as usize] as char);
-        out.push(BASE45_ALPHABET[b as usize] as char);
-        out.push(BASE45_ALPHABET[a as usize] as char);
-        i += 2;
+    while i < BASE45_ALPHABET.len() {
+        table[i] = BASE45_ALPHABET[i];
+        i += 1;
+    }
+    table
+};
+
+/// `x * DIV45_MUL >> DIV45_SHIFT == x / 45` for every `x <= 65535`.
+pub(crate) const DIV45_MUL: u32 = 11651;
+pub(crate) const DIV45_SHIFT: u32 = 19;
+
+/// `x / 45` by reciprocal multiplication, exact for `x <= 65535`.
+#[inline(always)]
+const fn div45(x: u32) -> u32 {
+    (x * DIV45_MUL) >> DIV45_SHIFT
+}
+
+/// Split a 2-byte group value into its 3 base-45 digits, least significant first.
+#[inline]
+pub(crate) const fn pair_digits(x: u16) -> [u8; 3] {
+    let x = x as u32;
+    let q = div45(x);
+    let a = div45(q); // most significant digit (0..=32)
+    let b = q - a * 45;
+    let c = x - q * 45; // least significant digit
+    [c as u8, b as u8, a as u8]
+}
+
+/// Split a trailing single byte into its 2 base-45 digits, least significant first.
+#[inline]
+pub(crate) const fn single_digits(x: u8) -> [u8; 2] {
+    let a = div45(x as u32);
+    [(x as u32 - a * 45) as u8, a as u8]
+}
+
+/// Encode a 2-byte group value into its 3 Base45 characters.
+#[inline]
+pub(crate) const fn encode_pair(x: u16) -> [u8; 3] {
+    // Base45 outputs least-significant digit first
+    let [c, b, a] = pair_digits(x);
+    [
+        ALPHABET_64[(c & 0x3F) as usize],
+        ALPHABET_64[(b & 0x3F) as usize],
+        ALPHABET_64[(a & 0x3F) as usize],
+    ]
+}
+
+/// The 3 Base45 characters of every 2-byte group value: 192 KiB, behind the `big-tables`
+/// feature.
+#[cfg(feature = "big-tables")]
+static PAIR_TABLE: [[u8; 3]; 65536] = {
+    let mut table = [[0; 3]; 65536];
+    let mut x = 0;
+    while x < table.len() {
+        table[x] = encode_pair(x as u16);
+        x += 1;
     }
-    if i < input.len() {
-        let x = input[i] as u16;
-        let b = x % 45;
-        let a = x / 45;
-        // Base45 outputs least-significant digit first for single byte too
-        out.push(BASE45_ALPHABET[b as usize] as char);
-        out.push(BASE45_ALPHABET[a as usize] as char);
+    table
+};
+
+/// Encode a trailing single byte into its 2 Base45 characters.
+#[inline]
+pub(crate) const fn encode_single(x: u8) -> [u8; 2] {
+    // Base45 outputs least-significant digit first for single byte too
+    let [b, a] = single_digits(x);
+    [
+        ALPHABET_64[(b & 0x3F) as usize],
+        ALPHABET_64[(a & 0x3F) as usize],
+    ]
+}
+
+/// Number of Base45 characters produced by encoding `n` bytes.
+pub const fn encoded_len(n: usize) -> usize {
+    n / 2 * 3 + n % 2 * 2
+}
+
+/// Number of bytes a Base45 string decodes to, checking only the group structure.
+/// Returns `Dangling` if the length leaves a single trailing character; the alphabet and
+/// group values are not checked, so a full decode may still fail.
+pub const fn decoded_len(s: &str) -> Result<usize, Base45Error> {
+    let n = s.len();
+    if n % 3 == 1 {
+        return Err(Base45Error::dangling(n - 1));
     }
+    Ok(n / 3 * 2 + n % 3 / 2)
+}
+
+/// Encode arbitrary bytes into a Base45 string.
+/// Groups of 2 bytes produce 3 characters; a final single byte produces 2 characters.
+/// Accepts anything byte-like (`&[u8]`, `Vec<u8>`, arrays, `&str`); text is encoded as its UTF-8 bytes.
+#[cfg(feature = "alloc")]
+pub fn encode<T: AsRef<[u8]>>(input: T) -> String {
+    ascii_string(encode_to_ascii(input))
+}
+
+/// Encode like [`encode`], returning the ASCII output as bytes, for callers that write it
+/// straight to a socket or file.
+///
+/// ```
+/// assert_eq!(qr_base45::encode_to_ascii(b"ietf!"), b"QED8WEX0");
+/// ```
+#[cfg(feature = "alloc")]
+pub fn encode_to_ascii<T: AsRef<[u8]>>(input: T) -> Vec<u8> {
+    let input = input.as_ref();
+    let mut out = alloc::vec![0; encoded_len(input.len())];
+    encode_exact(input, &mut out);
     out
 }
 
+/// Wrap encoder output, which is always ASCII, as a `String`.
+#[cfg(feature = "alloc")]
+fn ascii_string(out: Vec<u8>) -> String {
+    #[cfg(feature = "unsafe-fast")]
+    // SAFETY: every byte written comes from BASE45_ALPHABET, which is pure ASCII.
+    let out = unsafe { String::from_utf8_unchecked(out) };
+    #[cfg(not(feature = "unsafe-fast"))]
+    let out = String::from_utf8(out).expect("Base45 output is ASCII");
+    out
+}
+
+/// Encode like [`encode`], but fail with `TooLarge` instead of producing more than `max_len`
+/// characters (`None` means unlimited). For example, a version 40 QR code at ECC level L holds
+/// at most 4296 alphanumeric characters. The check happens before any allocation.
+#[cfg(feature = "alloc")]
+pub fn try_encode<T: AsRef<[u8]>>(input: T, max_len: Option<usize>) -> Result<String, Base45Error> {
+    let input = input.as_ref();
+    if max_len.is_some_and(|max| encoded_len(input.len()) > max) {
+        return Err(Base45Error::new(ErrorKind::TooLarge));
+    }
+    Ok(encode(input))
+}
+
+/// Encode arbitrary bytes, appending the Base45 output to `out`.
+/// Existing contents of `out` are kept; returns the number of characters written.
+#[cfg(feature = "alloc")]
+pub fn encode_into<T: AsRef<[u8]>>(input: T, out: &mut String) -> usize {
+    encode_append(input.as_ref(), out)
+}
+
+/// Byte-oriented appender behind the `unsafe-fast` feature: grows `out` once, then writes the
+/// groups in place.
+#[cfg(all(feature = "alloc", feature = "unsafe-fast"))]
+fn encode_append(input: &[u8], out: &mut String) -> usize {
+    let len = encoded_len(input.len());
+    // SAFETY: only ASCII bytes from BASE45_ALPHABET are written, so `out` stays valid UTF-8.
+    let bytes = unsafe { out.as_mut_vec() };
+    let start = bytes.len();
+    bytes.resize(start + len, 0);
+    encode_exact(input, &mut bytes[start..]);
+    len
+}
+
+#[cfg(all(feature = "alloc", not(feature = "unsafe-fast")))]
+fn encode_append(input: &[u8], out: &mut String) -> usize {
+    out.reserve(encoded_len(input.len()));
+    // Runs of stack-encoded ASCII, so only the new output is checked as UTF-8
+    let Ok(()) = encode_with(input, |run| {
+        out.push_str(run);
+        Ok::<_, core::convert::Infallible>(())
+    });
+    encoded_len(input.len())
+}
+
+/// Encode arbitrary bytes as ASCII into a caller-provided buffer, without allocating.
+/// Returns the number of bytes written, or `BufferTooSmall` if `out` cannot hold the whole encoding
+/// (in which case `out` is left untouched).
+pub fn encode_slice(input: &[u8], out: &mut [u8]) -> Result<usize, Base45Error> {
+    no_panic!({
+        let len = encoded_len(input.len());
+        let Some(out) = out.get_mut(..len) else {
+            return Err(Base45Error::new(ErrorKind::BufferTooSmall));
+        };
+        encode_exact(input, out);
+        Ok(len)
+    })
+}
+
+/// Encode `input` into `out`, which must be exactly `encoded_len(input.len())` bytes long.
+#[inline(always)]
+fn encode_exact(input: &[u8], out: &mut [u8]) {
+    let n = simd::encode_blocks(input, out);
+    let (Some(input), Some(out)) = (input.get(n..), out.get_mut(n / 2 * 3..)) else {
+        return;
+    };
+    // Two groups per step, unless the pair table makes a lookup per group cheaper
+    #[cfg(not(feature = "big-tables"))]
+    let (input, out) = {
+        let mut quads = input.chunks_exact(4);
+        let mut dst = out.chunks_exact_mut(6);
+        for (quad, d) in (&mut quads).zip(&mut dst) {
+            if let (Ok(quad), Ok(d)) = (quad.try_into(), <&mut [u8; 6]>::try_from(d)) {
+                *d = swar::encode_quad(quad);
+            }
+        }
+        // At most 3 bytes remain, with exactly their encoded length of output
+        (quads.remainder(), dst.into_remainder())
+    };
+    let mut pairs = input.chunks_exact(2);
+    let mut dst = out.chunks_exact_mut(3);
+    for (pair, d) in (&mut pairs).zip(&mut dst) {
+        let x = (pair[0] as u16) * 256 + (pair[1] as u16);
+        #[cfg(feature = "big-tables")]
+        d.copy_from_slice(&PAIR_TABLE[x as usize]);
+        #[cfg(not(feature = "big-tables"))]
+        d.copy_from_slice(&encode_pair(x));
+    }
+    if let ([last], [d0, d1]) = (pairs.remainder(), dst.into_remainder()) {
+        [*d0, *d1] = encode_single(*last);
+    }
+}
+
 /// Decode a Base45 string back to raw bytes.
 /// Accepts only the RFC 9285 alphabet; returns errors for invalid chars, dangling final char, or overflow.
+#[cfg(feature = "alloc")]
 pub fn decode(s: &str) -> Result<Vec<u8>, Base45Error> {
+    let mut out: Vec<u8> = Vec::with_capacity(s.len());
+    decode_append(s, &mut out)?;
+    Ok(out)
+}
+
+/// Decode strictly like [`decode`], but refuse with `TooLarge` any input that would decode to
+/// more than `max_len` bytes. The check runs on the input length alone, before allocating.
+#[cfg(feature = "alloc")]
+pub fn decode_with_limit(s: &str, max_len: usize) -> Result<Vec<u8>, Base45Error> {
+    if s.len() / 3 * 2 + s.len() % 3 / 2 > max_len {
+        return Err(Base45Error::new(ErrorKind::TooLarge));
+    }
+    decode(s)
+}
+
+/// Decode the Base45 text at the start of `s`, stopping at the first character outside the alphabet.
+/// Returns the decoded bytes and the number of input bytes consumed, so the caller can continue
+/// parsing the rest of a larger message at `&s[consumed..]`.
+///
+/// The consumed run itself must still be well formed: a dangling final character or an
+/// overflowing group in it is an error.
+#[cfg(feature = "alloc")]
+pub fn decode_partial(s: &str) -> Result<(Vec<u8>, usize), Base45Error> {
     let bytes = s.as_bytes();
-    let mut out: Vec<u8> = Vec::with_capacity(bytes.len());
-    let mut i = 0;
-    while i + 2 < bytes.len() {
-        // Input is least-significant digit first: c (lsd), b, a (msd)
-        let c0 = b45_val(bytes[i]).ok_or(Base45Error::InvalidChar)? as u32;
-        let c1 = b45_val(bytes[i + 1]).ok_or(Base45Error::InvalidChar)? as u32;
-        let c2 = b45_val(bytes[i + 2]).ok_or(Base45Error::InvalidChar)? as u32;
-        let x: u32 = c2 * 45 * 45 + c1 * 45 + c0; // 0..(45^3 - 1)
-        if x > 65535 {
-            return Err(Base45Error::Overflow);
-        }
-        out.push((x / 256) as u8);
-        out.push((x % 256) as u8);
-        i += 3;
-    }
-    if i < bytes.len() {
-        if i + 1 >= bytes.len() {
-            // Single trailing character: report InvalidChar if it's not in alphabet, otherwise Dangling
-            if b45_val(bytes[i]).is_none() {
-                return Err(Base45Error::InvalidChar);
+    let consumed = bytes
+        .iter()
+        .position(|&b| b45_val(b).is_none())
+        .unwrap_or(bytes.len());
+    let mut out = Vec::with_capacity(consumed / 3 * 2 + 1);
+    decode_groups(&bytes[..consumed], &mut out)?;
+    Ok((out, consumed))
+}
+
+/// Decode `s`, reusing its allocation for the output instead of allocating a second buffer.
+/// Output is always shorter than the input, so each group is written over bytes already read.
+#[cfg(feature = "alloc")]
+pub fn decode_in_place(s: String) -> Result<Vec<u8>, Base45Error> {
+    decode_vec_in_place(s.into_bytes())
+}
+
+/// [`decode_in_place`] for text that may not be UTF-8.
+#[cfg(feature = "alloc")]
+fn decode_vec_in_place(mut buf: Vec<u8>) -> Result<Vec<u8>, Base45Error> {
+    // Output only overwrites the first 2/3 of the groups read, so the input from there on is intact
+    let res = decode_bytes_in_place(&mut buf);
+    let len = res.map_err(|e| {
+        let intact = e.position / 3 * 2;
+        char_err_at(&buf[intact..], intact)(e)
+    })?;
+    buf.truncate(len);
+    Ok(buf)
+}
+
+/// Decode the Base45 text in `buf` in place, returning the decoded length. The decoded bytes
+/// occupy `buf[..len]`; on error the contents of `buf` are unspecified.
+pub fn decode_bytes_in_place(buf: &mut [u8]) -> Result<usize, Base45Error> {
+    no_panic!({
+        let (mut i, mut j) = (0, 0);
+        while let Some(group) = buf.get(i..i + 3) {
+            let pair = decode_triple(group, i)?;
+            // j <= i and the group is read before writing, so unread input is never overwritten
+            if let Some(d) = buf.get_mut(j..j + 2) {
+                d.copy_from_slice(&pair);
             }
-            return Err(Base45Error::Dangling);
+            i += 3;
+            j += 2;
         }
-        let c0 = b45_val(bytes[i]).ok_or(Base45Error::InvalidChar)? as u32;
-        let c1 = b45_val(bytes[i + 1]).ok_or(Base45Error::InvalidChar)? as u32;
-        let x: u32 = c1 * 45 + c0; // 0..(45^2 - 1)
-        if x > 255 {
-            return Err(Base45Error::Overflow);
+        if let Some(tail @ [_, ..]) = buf.get(i..) {
+            let b = decode_tail(tail, i)?;
+            if let Some(d) = buf.get_mut(j) {
+                *d = b;
+            }
+            j += 1;
         }
-        out.push(x as u8);
-    }
+        Ok(j)
+    })
+}
+
+/// Decode Base45 text given as raw bytes (e.g. straight from a scanner), skipping UTF-8 validation.
+/// Bytes outside the alphabet are reported as `InvalidByte` with their offset in `input`.
+#[cfg(feature = "alloc")]
+pub fn decode_bytes(input: &[u8]) -> Result<Vec<u8>, Base45Error> {
+    let mut out: Vec<u8> = Vec::with_capacity(input.len());
+    decode_groups(input, &mut out)?;
     Ok(out)
 }
 
-#[cfg(test)]
+/// Decode a Base45 string, appending the raw bytes to `out`.
+/// Returns the number of bytes appended. On error `out` is restored to its original length.
+#[cfg(feature = "alloc")]
+pub fn decode_append(s: &str, out: &mut Vec<u8>) -> Result<usize, Base45Error> {
+    let start = out.len();
+    let res = decode_groups(s.as_bytes(), out).map_err(char_err(s.as_bytes()));
+    if res.is_err() {
+        out.truncate(start);
+    }
+    res.map(|()| out.len() - start)
+}
+
+/// The `&str` entry points report alphabet violations as `InvalidChar`, naming the full
+/// character of `src` that starts at the offending byte, and keep an excerpt of `src` for display.
+#[inline]
+fn char_err(src: &[u8]) -> impl Fn(Base45Error) -> Base45Error + '_ {
+    char_err_at(src, 0)
+}
+
+/// [`char_err`] for a tail of the input: `src` starts at byte offset `base`.
+#[inline]
+fn char_err_at(src: &[u8], base: usize) -> impl Fn(Base45Error) -> Base45Error + '_ {
+    move |e| {
+        let e = match e.byte() {
+            Some(byte) => {
+                let ch = e.position.checked_sub(base).and_then(|i| char_at(src, i));
+                Base45Error::invalid_char(ch.unwrap_or(byte as char), e.position)
+            }
+            None => e,
+        };
+        e.with_context(src, base)
+    }
+}
+
+/// The UTF-8 character starting at byte `index` of `src`, if one does.
+#[inline]
+fn char_at(src: &[u8], index: usize) -> Option<char> {
+    // Decoded by hand: `str::from_utf8` is opaque to the `no-panic` check
+    let (&lead, rest) = src.get(index..)?.split_first()?;
+    let (width, min, bits) = match lead {
+        0x00..0x80 => return Some(lead as char),
+        0xC0..0xE0 => (1, 0x80, lead & 0x1F),
+        0xE0..0xF0 => (2, 0x800, lead & 0x0F),
+        0xF0..0xF8 => (3, 0x1_0000, lead & 0x07),
+        _ => return None,
+    };
+    let mut cp = bits as u32;
+    for &b in rest.get(..width)? {
+        if b & 0xC0 != 0x80 {
+            return None;
+        }
+        cp = cp << 6 | (b & 0x3F) as u32;
+    }
+    char::from_u32(cp).filter(|_| cp >= min)
+}
+
+/// Look up the digit at `group[k]`, reporting the absolute index `at + k` if it is not in the alphabet.
+#[inline(always)]
+const fn digit(group: &[u8], k: usize, at: usize) -> Result<u32, Base45Error> {
+    let byte = group[k];
+    match b45_val(byte) {
+        Some(v) => Ok(v as u32),
+        None => Err(Base45Error::invalid_byte(byte, at + k)),
+    }
+}
+
+/// Decode a full 3-character group starting at input offset `at` into its 2 bytes.
+#[inline(always)]
+pub(crate) const fn decode_triple(group: &[u8], at: usize) -> Result<[u8; 2], Base45Error> {
+    // Input is least-significant digit first: c (lsd), b, a (msd)
+    let c0 = DECODE_TABLE[group[0] as usize];
+    let c1 = DECODE_TABLE[group[1] as usize];
+    let c2 = DECODE_TABLE[group[2] as usize];
+    // Digits are below 45 and INVALID_DIGIT has the top bit set, so one test covers all three
+    if (c0 | c1 | c2) & 0x80 != 0 {
+        return Err(invalid_in(group, at));
+    }
+    pair_value(c0 as u32, c1 as u32, c2 as u32, at)
+}
+
+/// The error for the first character of `group` outside the alphabet; `group` must have one.
+#[cold]
+const fn invalid_in(group: &[u8], at: usize) -> Base45Error {
+    let mut k = 0;
+    while k < group.len() {
+        if b45_val(group[k]).is_none() {
+            return Base45Error::invalid_byte(group[k], at + k);
+        }
+        k += 1;
+    }
+    Base45Error::new(ErrorKind::InvalidByte)
+}
+
+/// Combine 3 digits (least significant first) of the group at offset `at` into its 2 bytes.
+#[inline(always)]
+pub(crate) const fn pair_value(
+    c0: u32,
+    c1: u32,
+    c2: u32,
+    at: usize,
+) -> Result<[u8; 2], Base45Error> {
+    let x: u32 = c2 * 45 * 45 + c1 * 45 + c0; // 0..(45^3 - 1)
+    if x > 65535 {
+        return Err(Base45Error::overflow(at, x));
+    }
+    Ok([(x / 256) as u8, (x % 256) as u8])
+}
+
+/// Combine the 2 digits (least significant first) of the trailing group at offset `at` into its byte.
+#[inline(always)]
+pub(crate) const fn single_value(c0: u32, c1: u32, at: usize) -> Result<u8, Base45Error> {
+    let x: u32 = c1 * 45 + c0; // 0..(45^2 - 1)
+    if x > 255 {
+        return Err(Base45Error::tail_overflow(at, x));
+    }
+    Ok(x as u8)
+}
+
+/// Decode the trailing partial group (1 or 2 characters) starting at offset `at` into its single byte.
+#[inline(always)]
+pub(crate) const fn decode_tail(group: &[u8], at: usize) -> Result<u8, Base45Error> {
+    let c0 = const_try!(digit(group, 0, at));
+    if group.len() == 1 {
+        // Single trailing character: an invalid char is reported above, otherwise Dangling
+        return Err(Base45Error::dangling(at));
+    }
+    let c1 = const_try!(digit(group, 1, at));
+    single_value(c0, c1, at)
+}
+
+/// Check whether `s` is valid Base45 (alphabet, group lengths, group values) without decoding it.
+pub fn is_valid(s: &str) -> bool {
+    no_panic!({ check_groups(s.as_bytes()).is_ok() })
+}
+
+/// Run the full decode checks over `bytes` without producing output.
+#[inline]
+pub(crate) fn check_groups(bytes: &[u8]) -> Result<(), Base45Error> {
+    let mut groups = bytes.chunks_exact(3);
+    let mut at = 0;
+    for group in &mut groups {
+        decode_triple(group, at)?;
+        at += 3;
+    }
+    let tail = groups.remainder();
+    if !tail.is_empty() {
+        decode_tail(tail, at)?;
+    }
+    Ok(())
+}
+
+#[cfg(feature = "alloc")]
+fn decode_groups(bytes: &[u8], out: &mut Vec<u8>) -> Result<(), Base45Error> {
+    out.reserve(bytes.len() / 3 * 2 + bytes.len() % 3 / 2);
+    let start = out.len();
+    out.resize(start + simd::decoded_block_len(bytes.len()), 0);
+    let mut at = simd::decode_blocks(bytes, &mut out[start..]);
+    out.truncate(start + at / 3 * 2);
+    let mut chunks = bytes[at..].chunks_exact(swar::CHUNK);
+    for chunk in &mut chunks {
+        let mut buf = [0; swar::CHUNK / 3 * 2];
+        if let Ok(chunk) = chunk.try_into() {
+            swar::decode_chunk(chunk, &mut buf, at)?;
+        }
+        out.extend_from_slice(&buf);
+        at += swar::CHUNK;
+    }
+    let mut sextets = chunks.remainder().chunks_exact(6);
+    for sextet in &mut sextets {
+        if let Ok(sextet) = sextet.try_into() {
+            out.extend_from_slice(&swar::decode_sextet(sextet, at)?);
+        }
+        at += 6;
+    }
+    let mut groups = sextets.remainder().chunks_exact(3);
+    for group in &mut groups {
+        out.extend_from_slice(&decode_triple(group, at)?);
+        at += 3;
+    }
+    let tail = groups.remainder();
+    if !tail.is_empty() {
+        out.push(decode_tail(tail, at)?);
+    }
+    Ok(())
+}
+
+/// Decode a Base45 string into a caller-provided buffer, without allocating.
+/// Returns the number of bytes written, or `BufferTooSmall` if `out` cannot hold the decoded data.
+/// On error the contents of `out` are unspecified.
+/// With the `heapless` or `arrayvec` feature, `decode_to_heapless` or `decode_to_array_vec`
+/// decodes into a fixed-capacity vector.
+pub fn decode_slice(s: &str, out: &mut [u8]) -> Result<usize, Base45Error> {
+    no_panic!({
+        let bytes = s.as_bytes();
+        let len = bytes.len() / 3 * 2 + bytes.len() % 3 / 2;
+        let Some(out) = out.get_mut(..len) else {
+            return Err(Base45Error::new(ErrorKind::BufferTooSmall));
+        };
+        decode_exact(bytes, out).map_err(char_err(bytes))?;
+        Ok(len)
+    })
+}
+
+/// Decode the Base45 text `bytes` into `out`, which must be exactly its decoded length. Errors
+/// report byte values and positions relative to `bytes`.
+#[inline(always)]
+fn decode_exact(bytes: &[u8], out: &mut [u8]) -> Result<(), Base45Error> {
+    let mut at = simd::decode_blocks(bytes, out);
+    let (Some(rest), Some(out)) = (bytes.get(at..), out.get_mut(at / 3 * 2..)) else {
+        return Ok(());
+    };
+    let mut chunks = rest.chunks_exact(swar::CHUNK);
+    let mut dst = out.chunks_exact_mut(swar::CHUNK / 3 * 2);
+    for (chunk, d) in (&mut chunks).zip(&mut dst) {
+        if let (Ok(chunk), Ok(d)) = (chunk.try_into(), d.try_into()) {
+            swar::decode_chunk(chunk, d, at)?;
+        }
+        at += swar::CHUNK;
+    }
+    let mut sextets = chunks.remainder().chunks_exact(6);
+    let mut dst = dst.into_remainder().chunks_exact_mut(4);
+    for (sextet, d) in (&mut sextets).zip(&mut dst) {
+        if let (Ok(sextet), Ok(d)) = (sextet.try_into(), <&mut [u8; 4]>::try_from(d)) {
+            *d = swar::decode_sextet(sextet, at)?;
+        }
+        at += 6;
+    }
+    // At most one full group and a tail remain, with exactly their decoded length of output
+    let mut groups = sextets.remainder().chunks_exact(3);
+    let mut dst = dst.into_remainder().chunks_exact_mut(2);
+    for (group, d) in (&mut groups).zip(&mut dst) {
+        d.copy_from_slice(&decode_triple(group, at)?);
+        at += 3;
+    }
+    let tail = groups.remainder();
+    if !tail.is_empty() {
+        let b = decode_tail(tail, at)?;
+        if let [d] = dst.into_remainder() {
+            *d = b;
+        }
+    }
+    Ok(())
+}
+
+/// Encode a fixed-size input into a stack array of ASCII characters, no heap involved.
+/// `M` must equal `encoded_len(N)`; a mismatch is rejected at compile time.
+///
+/// ```
+/// let hash = [0xABu8; 32];
+/// let out: [u8; 48] = qr_base45::encode_array(&hash);
+/// assert_eq!(&out[..], qr_base45::encode(hash).as_bytes());
+/// ```
+///
+/// ```compile_fail
+/// let _: [u8; 4] = qr_base45::encode_array(b"AB");
+/// ```
+pub const fn encode_array<const N: usize, const M: usize>(input: &[u8; N]) -> [u8; M] {
+    const {
+        assert!(
+            M == encoded_len(N),
+            "output array length must equal encoded_len(N)"
+        )
+    };
+    let mut out = [0u8; M];
+    let (mut i, mut j) = (0, 0);
+    while i + 1 < N {
+        let [c, b, a] = encode_pair((input[i] as u16) * 256 + (input[i + 1] as u16));
+        out[j] = c;
+        out[j + 1] = b;
+        out[j + 2] = a;
+        i += 2;
+        j += 3;
+    }
+    if i < N {
+        let [b, a] = encode_single(input[i]);
+        out[j] = b;
+        out[j + 1] = a;
+    }
+    out
+}
+
+/// Decode a Base45 string literal at compile time into a fixed-size array.
+///
+/// ```
+/// const KEY: [u8; 5] = qr_base45::decode_const("QED8WEX0");
+/// assert_eq!(&KEY, b"ietf!");
+/// ```
+///
+/// # Panics
+/// If `s` is not valid Base45 or does not decode to exactly `N` bytes. In a `const` context
+/// this is a compile error.
+pub const fn decode_const<const N: usize>(s: &str) -> [u8; N] {
+    let bytes = s.as_bytes();
+    if bytes.len() % 3 == 1 || bytes.len() / 3 * 2 + bytes.len() % 3 / 2 != N {
+        panic!("base45 input does not decode to the requested length");
+    }
+    let mut out = [0u8; N];
+    let (mut i, mut j) = (0, 0);
+    while i < bytes.len() {
+        let (_, rest) = bytes.split_at(i);
+        if rest.len() >= 3 {
+            let (group, _) = rest.split_at(3);
+            match decode_triple(group, i) {
+                Ok([hi, lo]) => {
+                    out[j] = hi;
+                    out[j + 1] = lo;
+                }
+                Err(_) => panic!("invalid base45 input"),
+            }
+            i += 3;
+            j += 2;
+        } else {
+            match decode_tail(rest, i) {
+                Ok(b) => out[j] = b,
+                Err(_) => panic!("invalid base45 input"),
+            }
+            i += rest.len();
+        }
+    }
+    out
+}
+
+/// Decode a Base45 string literal at compile time into a `[u8; N]`, with `N` inferred from
+/// the literal. Invalid input fails the build.
+///
+/// ```
+/// const KEY: [u8; 5] = qr_base45::base45!("QED8WEX0");
+/// assert_eq!(&KEY, b"ietf!");
+/// assert_eq!(qr_base45::base45!("BB8"), *b"AB");
+/// ```
+///
+/// ```compile_fail
+/// let _ = qr_base45::base45!("ZZ"); // overflow
+/// ```
+#[macro_export]
+macro_rules! base45 {
+    ($s:expr) => {{
+        const S: &str = $s;
+        const N: usize = match $crate::decoded_len(S) {
+            Ok(n) => n,
+            Err(_) => panic!("dangling base45 input"),
+        };
+        const OUT: [u8; N] = $crate::decode_const::<N>(S);
+        OUT
+    }};
+}
+
+/// Decode a Base45 string into possibly uninitialized memory, e.g. ring-buffer slots,
+/// without zeroing it first. Returns the initialized prefix of `out` holding the decoded bytes,
+/// or `BufferTooSmall` if `out` cannot hold them.
+pub fn decode_uninit<'a>(
+    s: &str,
+    out: &'a mut [MaybeUninit<u8>],
+) -> Result<&'a mut [u8], Base45Error> {
+    no_panic!({
+        let bytes = s.as_bytes();
+        let len = bytes.len() / 3 * 2 + bytes.len() % 3 / 2;
+        let Some(out) = out.get_mut(..len) else {
+            return Err(Base45Error::new(ErrorKind::BufferTooSmall));
+        };
+        let mut groups = bytes.chunks_exact(3);
+        let mut dst = out.chunks_exact_mut(2);
+        let mut at = 0;
+        for (group, d) in (&mut groups).zip(&mut dst) {
+            let [hi, lo] = decode_triple(group, at).map_err(char_err(bytes))?;
+            d[0].write(hi);
+            d[1].write(lo);
+            at += 3;
+        }
+        let tail = groups.remainder();
+        if !tail.is_empty() {
+            let b = decode_tail(tail, at).map_err(char_err(bytes))?;
+            if let [d] = dst.into_remainder() {
+                d.write(b);
+            }
+        }
+        // SAFETY: all of out[..len] was written above, and MaybeUninit<u8> has the same layout as u8.
+        Ok(unsafe { core::slice::from_raw_parts_mut(out.as_mut_ptr().cast::<u8>(), len) })
+    })
+}
+
+#[cfg(all(test, feature = "alloc"))]
 mod tests {
     use super::*;
 
@@ -129,7 +907,7 @@ mod tests {
         // For a 1-byte group [u], b = u % 45; a = u / 45; and output chars are [b, a].
         // Edge cases at boundaries
         // [0x00, 0x00] -> x = 0; digits: c=0, b=0, a=0; output lsd-first -> "000"
-        assert_eq!(encode(&[0x00, 0x00]), "000");
+        assert_eq!(encode([0x00, 0x00]), "000");
 
         // From RFC examples and common vectors
         assert_eq!(encode(b"AB"), "BB8");
@@ -141,6 +919,302 @@ mod tests {
         assert_eq!(decode("QED8WEX0").unwrap(), b"ietf!");
     }
 
+    #[test]
+    fn output_is_always_ascii() {
+        // The `unsafe-fast` encoder relies on this to skip UTF-8 checks: cover every group value.
+        assert!(BASE45_ALPHABET.is_ascii());
+        for x in 0..=u16::MAX {
+            assert!(encode_pair(x).is_ascii());
+            assert_eq!(decode_triple(&encode_pair(x), 0).unwrap(), x.to_be_bytes());
+        }
+        for x in 0..=u8::MAX {
+            assert!(encode_single(x).is_ascii());
+            assert_eq!(decode_tail(&encode_single(x), 0).unwrap(), x);
+        }
+        let mut s = String::from("é");
+        encode_into([0xFF; 9], &mut s);
+        assert_eq!(s, format!("é{}", encode([0xFF; 9])));
+    }
+
+    #[test]
+    fn div45_is_exact() {
+        for x in 0..=u16::MAX as u32 {
+            assert_eq!(div45(x), x / 45);
+        }
+    }
+
+    #[test]
+    fn decode_table_inverts_alphabet() {
+        let valid = DECODE_TABLE.iter().filter(|&&d| d != INVALID_DIGIT).count();
+        assert_eq!(valid, 45);
+        for (i, &ch) in BASE45_ALPHABET.iter().enumerate() {
+            assert_eq!(DECODE_TABLE[ch as usize] as usize, i);
+        }
+        assert_eq!(DECODE_TABLE[b'a' as usize], INVALID_DIGIT);
+    }
+
+    #[test]
+    fn encode_accepts_byte_like_inputs() {
+        assert_eq!(encode(b"AB"), "BB8");
+        assert_eq!(encode(Vec::from(b"AB")), "BB8");
+        assert_eq!(encode(*b"AB"), "BB8");
+        assert_eq!(encode("AB"), "BB8");
+        assert_eq!(encode(String::from("AB")), "BB8");
+    }
+
+    #[test]
+    fn try_encode_limits() {
+        assert_eq!(try_encode(b"ietf!", None).unwrap(), "QED8WEX0");
+        assert_eq!(try_encode(b"ietf!", Some(8)).unwrap(), "QED8WEX0");
+        assert!(matches!(
+            try_encode(b"ietf!", Some(7)),
+            Err(e) if e.kind == ErrorKind::TooLarge
+        ));
+        assert!(matches!(
+            try_encode(vec![0u8; 2865], Some(4296)),
+            Err(e) if e.kind == ErrorKind::TooLarge
+        ));
+        assert_eq!(try_encode(vec![0u8; 2864], Some(4296)).unwrap().len(), 4296);
+    }
+
+    #[test]
+    fn encode_into_appends() {
+        let mut buf = String::from("HC1:");
+        assert_eq!(encode_into(b"AB", &mut buf), 3);
+        assert_eq!(encode_into(b"ietf!", &mut buf), 8);
+        assert_eq!(buf, "HC1:BB8QED8WEX0");
+        assert_eq!(encode_into(b"", &mut buf), 0);
+        assert_eq!(buf, "HC1:BB8QED8WEX0");
+    }
+
+    #[test]
+    fn length_calculators() {
+        for n in 0..64 {
+            let data = vec![0xFFu8; n];
+            let s = encode(&data);
+            assert_eq!(encoded_len(n), s.len());
+            assert_eq!(decoded_len(&s).unwrap(), n);
+        }
+        const LEN: usize = encoded_len(32);
+        assert_eq!(LEN, 48);
+        assert!(matches!(
+            decoded_len("BB8A"),
+            Err(e) if e.kind == ErrorKind::Dangling
+        ));
+        // Only the structure is checked, not the alphabet
+        assert_eq!(decoded_len("~~").unwrap(), 1);
+    }
+
+    #[test]
+    fn encode_slice_writes_ascii() {
+        let mut buf = [0u8; 16];
+        let n = encode_slice(b"ietf!", &mut buf).unwrap();
+        assert_eq!(&buf[..n], b"QED8WEX0");
+
+        // Exact fit works; one byte short is rejected without touching the buffer
+        let mut exact = [0u8; 3];
+        assert_eq!(encode_slice(b"AB", &mut exact).unwrap(), 3);
+        assert_eq!(&exact, b"BB8");
+        let mut short = [0u8; 7];
+        assert!(matches!(
+            encode_slice(b"ietf!", &mut short),
+            Err(e) if e.kind == ErrorKind::BufferTooSmall
+        ));
+        assert_eq!(short, [0u8; 7]);
+    }
+
+    #[cfg(feature = "big-tables")]
+    #[test]
+    fn pair_table_matches_swar() {
+        for x in 0..=u16::MAX {
+            let [a, b] = x.to_be_bytes();
+            let quad = swar::encode_quad(&[a, b, b, a]);
+            assert_eq!(PAIR_TABLE[x as usize], quad[..3]);
+        }
+    }
+
+    #[test]
+    fn decode_append_reuses_buffer() {
+        let mut buf = vec![0xAA];
+        assert_eq!(decode_append("BB8", &mut buf).unwrap(), 2);
+        assert_eq!(decode_append("QED8WEX0", &mut buf).unwrap(), 5);
+        assert_eq!(buf, b"\xAAABietf!");
+
+        // A failed decode leaves previously decoded data untouched
+        assert!(matches!(
+            decode_append("BB8ZZ", &mut buf),
+            Err(e) if e.kind == ErrorKind::TailOverflow
+        ));
+        assert_eq!(buf, b"\xAAABietf!");
+    }
+
+    #[test]
+    fn decode_slice_fills_buffer() {
+        let mut buf = [0u8; 8];
+        let n = decode_slice("QED8WEX0", &mut buf).unwrap();
+        assert_eq!(&buf[..n], b"ietf!");
+
+        let mut exact = [0u8; 5];
+        assert_eq!(decode_slice("QED8WEX0", &mut exact).unwrap(), 5);
+        let mut short = [0u8; 4];
+        assert!(matches!(
+            decode_slice("QED8WEX0", &mut short),
+            Err(e) if e.kind == ErrorKind::BufferTooSmall
+        ));
+        // Structural errors are still reported once capacity is sufficient
+        assert!(matches!(
+            decode_slice("BB8A", &mut buf),
+            Err(e) if e.kind == ErrorKind::Dangling
+        ));
+    }
+
+    #[test]
+    fn decode_limit() {
+        assert_eq!(decode_with_limit("QED8WEX0", 5).unwrap(), b"ietf!");
+        assert!(matches!(
+            decode_with_limit("QED8WEX0", 4),
+            Err(e) if e.kind == ErrorKind::TooLarge
+        ));
+        // Oversized input is rejected before it is looked at
+        let huge = "~".repeat(3000);
+        assert!(matches!(
+            decode_with_limit(&huge, 1024),
+            Err(e) if e.kind == ErrorKind::TooLarge
+        ));
+        assert!(matches!(
+            decode_with_limit("BB8A", 5),
+            Err(e) if e.kind == ErrorKind::Dangling
+        ));
+    }
+
+    #[test]
+    fn decode_partial_stops_at_foreign_char() {
+        assert_eq!(decode_partial("QED8WEX0").unwrap(), (b"ietf!".to_vec(), 8));
+        assert_eq!(decode_partial("BB8;rest").unwrap(), (b"AB".to_vec(), 3));
+        assert_eq!(decode_partial("").unwrap(), (Vec::new(), 0));
+        assert_eq!(decode_partial("~BB8").unwrap(), (Vec::new(), 0));
+        assert!(matches!(
+            decode_partial("BB8A;"),
+            Err(e) if e.kind == ErrorKind::Dangling
+        ));
+        assert!(matches!(
+            decode_partial("ZZ;"),
+            Err(e) if e.kind == ErrorKind::TailOverflow
+        ));
+    }
+
+    #[test]
+    fn in_place_decoding() {
+        let data: Vec<u8> = (0..=255).collect();
+        let s = encode(&data);
+        let ptr = s.as_ptr();
+        let out = decode_in_place(s).unwrap();
+        assert_eq!(out, data);
+        assert_eq!(out.as_ptr(), ptr);
+
+        let mut buf = *b"QED8WEX0";
+        let n = decode_bytes_in_place(&mut buf).unwrap();
+        assert_eq!(&buf[..n], b"ietf!");
+        assert!(matches!(
+            decode_in_place("BB8A".into()),
+            Err(e) if e.kind == ErrorKind::Dangling
+        ));
+        assert!(matches!(
+            decode_in_place("BB8~~".into()),
+            Err(e) if e.kind == ErrorKind::InvalidChar
+        ));
+    }
+
+    #[test]
+    fn fixed_size_encoding() {
+        const AB: [u8; 3] = encode_array(b"AB");
+        assert_eq!(&AB, b"BB8");
+        let out: [u8; 8] = encode_array(b"ietf!");
+        assert_eq!(&out, b"QED8WEX0");
+        let empty: [u8; 0] = encode_array(&[]);
+        assert_eq!(empty, [0u8; 0]);
+    }
+
+    #[test]
+    fn const_decoding() {
+        const HELLO: [u8; 7] = decode_const("%69 VD92EX0");
+        const EMPTY: [u8; 0] = decode_const("");
+        const FULL: [u8; 2] = decode_const("FGW");
+        assert_eq!(&HELLO, b"Hello!!");
+        assert_eq!(EMPTY, [0u8; 0]);
+        assert_eq!(FULL, [0xFF, 0xFF]);
+    }
+
+    #[test]
+    fn base45_macro() {
+        const CERT: [u8; 7] = base45!("%69 VD92EX0");
+        assert_eq!(&CERT, b"Hello!!");
+        assert_eq!(base45!(""), [0u8; 0]);
+    }
+
+    #[test]
+    #[should_panic(expected = "invalid base45 input")]
+    fn const_decoding_rejects_invalid() {
+        decode_const::<2>("GGW");
+    }
+
+    #[test]
+    #[should_panic(expected = "requested length")]
+    fn const_decoding_rejects_wrong_length() {
+        decode_const::<4>("QED8WEX0");
+    }
+
+    #[test]
+    fn decode_into_uninit() {
+        let mut slots = [MaybeUninit::<u8>::uninit(); 8];
+        let out = decode_uninit("QED8WEX0", &mut slots).unwrap();
+        assert_eq!(out, b"ietf!");
+        out[0] = b'I';
+        assert_eq!(unsafe { slots[0].assume_init() }, b'I');
+
+        let mut short = [MaybeUninit::<u8>::uninit(); 4];
+        assert!(matches!(
+            decode_uninit("QED8WEX0", &mut short),
+            Err(e) if e.kind == ErrorKind::BufferTooSmall
+        ));
+        assert!(matches!(
+            decode_uninit("BB8~", &mut slots),
+            Err(e) if e.kind == ErrorKind::InvalidChar
+        ));
+    }
+
+    #[test]
+    fn decode_bytes_reports_offsets() {
+        assert_eq!(decode_bytes(b"QED8WEX0").unwrap(), b"ietf!");
+        let err = decode_bytes(b"BB8Q\xFFD").unwrap_err();
+        assert_eq!(
+            (err.kind, err.byte(), err.position),
+            (ErrorKind::InvalidByte, Some(0xFF), 4)
+        );
+        // The first bad character of a group is reported, not the last
+        let err = decode_bytes(b"BB8Qa\xFF").unwrap_err();
+        assert_eq!((err.byte(), err.position), (Some(b'a'), 4));
+        let err = decode_bytes(b"BB8a").unwrap_err();
+        assert_eq!(
+            (err.kind, err.byte(), err.position),
+            (ErrorKind::InvalidByte, Some(b'a'), 3)
+        );
+        assert!(matches!(
+            decode_bytes(b"BB8A"),
+            Err(e) if e.kind == ErrorKind::Dangling
+        ));
+    }
+
+    #[test]
+    fn validity_check() {
+        for s in ["", "BB8", "QED8WEX0", "%69 VD92EX0", "FGW"] {
+            assert!(is_valid(s), "{s:?}");
+        }
+        for s in ["A", "BB8A", "ZZ", ":::", "bb8", "BB8\n", "😀"] {
+            assert!(!is_valid(s), "{s:?}");
+        }
+    }
+
     #[test]
     fn errors() {
         // Error categories under test:
@@ -148,15 +1222,120 @@ mod tests {
         // - Dangling: incomplete group (e.g., single trailing valid character)
         // - Overflow: numeric value exceeds maximum for the group
         // Invalid characters and structural errors
-        assert!(matches!(decode("\t"), Err(Base45Error::InvalidChar))); // '\t' not in Base45 alphabet
-        assert!(matches!(decode("\n"), Err(Base45Error::InvalidChar))); // '\n' not in Base45 alphabet
+        assert!(matches!(decode("\t"), Err(e) if e.kind == ErrorKind::InvalidChar)); // '\t' not in Base45 alphabet
+        assert!(matches!(decode("\n"), Err(e) if e.kind == ErrorKind::InvalidChar)); // '\n' not in Base45 alphabet
         // Overflow cases
         // 3-char group with max digits -> value > 65535
-        assert!(matches!(decode(":::"), Err(Base45Error::Overflow))); // ':::' -> 44*45^2 + 44*45 + 44 = 91124 > 65535
+        assert!(matches!(decode(":::"), Err(e) if e.kind == ErrorKind::Overflow)); // ':::' -> 44*45^2 + 44*45 + 44 = 91124 > 65535
         // 2-char group producing >255
-        assert!(matches!(decode("ZZ"), Err(Base45Error::Overflow))); // 'ZZ' -> 35*45 + 35 = 1610 > 255
+        assert!(matches!(decode("ZZ"), Err(e) if e.kind == ErrorKind::TailOverflow)); // 'ZZ' -> 35*45 + 35 = 1610 > 255
+
+        assert!(matches!(decode("A"), Err(e) if e.kind == ErrorKind::Dangling)); // single valid char -> incomplete group
+        assert!(matches!(decode("😀"), Err(e) if e.kind == ErrorKind::InvalidChar)); // not in Base45 alphabet
+    }
+
+    #[test]
+    fn invalid_char_position() {
+        let err = decode("BB8Q~D").unwrap_err();
+        assert_eq!(err, Base45Error::invalid_char('~', 4));
+        assert_eq!(
+            (err.kind, err.ch(), err.position),
+            (ErrorKind::InvalidChar, Some('~'), 4)
+        );
+        assert_eq!(
+            err.to_string(),
+            "invalid base45 character '~' at index 4: BB8Q{~}D"
+        );
+        // Multi-byte characters are reported whole, at their starting byte
+        assert_eq!(decode("BB8😀"), Err(Base45Error::invalid_char('😀', 3)));
+        assert_eq!(
+            decode_in_place("QED8W~X0".to_owned()),
+            Err(Base45Error::invalid_char('~', 5))
+        );
+        assert_eq!(
+            crate::DecodeOptions::new()
+                .strip_prefix("HC1:")
+                .decode("HC1:BB8-~"),
+            Err(Base45Error::invalid_char('~', 8))
+        );
+        assert_eq!(
+            crate::decode_iter("BB8é".chars()).last(),
+            Some(Err(Base45Error::invalid_char('é', 3)))
+        );
+    }
+
+    #[test]
+    fn errors_compare_by_value() {
+        let err = decode("BB8A").unwrap_err();
+        let copy = err;
+        assert_eq!(err, copy);
+        assert_eq!(err, Base45Error::dangling(3));
+        assert_ne!(err, decode("A").unwrap_err());
+        assert_eq!(
+            decode_with_limit("BB8BB8", 1),
+            Err(ErrorKind::TooLarge.into())
+        );
+    }
+
+    #[test]
+    fn error_spans() {
+        let src = "BB8😀";
+        let span = decode(src).unwrap_err().span().unwrap();
+        assert_eq!(&src[span], "😀");
+        assert_eq!(decode("BB8:::").unwrap_err().span(), Some(3..6));
+        assert_eq!(decode("BB8ZZ").unwrap_err().span(), Some(3..5));
+        assert_eq!(decode("BB8A").unwrap_err().span(), Some(3..4));
+        assert_eq!(Base45Error::new(ErrorKind::TooLarge).span(), None);
+    }
+
+    #[cfg(feature = "miette")]
+    #[test]
+    fn miette_labels_the_span() {
+        use miette::{Diagnostic, GraphicalReportHandler, GraphicalTheme, Report};
 
-        assert!(matches!(decode("A"), Err(Base45Error::Dangling))); // single valid char -> incomplete group
-        assert!(matches!(decode("😀"), Err(Base45Error::InvalidChar))); // not in Base45 alphabet
+        let err = decode("QED8WEX0BB8~").unwrap_err();
+        assert_eq!(err.code().unwrap().to_string(), "qr_base45::InvalidChar");
+        let label = err.labels().unwrap().next().unwrap();
+        assert_eq!((label.offset(), label.len()), (11, 1));
+        assert!(Base45Error::new(ErrorKind::TooLarge).labels().is_none());
+
+        let report = Report::new(err).with_source_code("QED8WEX0BB8~");
+        let mut out = String::new();
+        GraphicalReportHandler::new_themed(GraphicalTheme::unicode_nocolor())
+            .render_report(&mut out, report.as_ref())
+            .unwrap();
+        assert!(out.contains("QED8WEX0BB8~"), "{out}");
+        assert!(out.contains("not in the Base45 alphabet"), "{out}");
+    }
+
+    #[test]
+    fn structural_error_position() {
+        // ":::" = 44 + 44*45 + 44*45^2
+        let err = decode("BB8:::").unwrap_err();
+        assert_eq!(
+            (err.kind, err.position, err.len),
+            (ErrorKind::Overflow, 3, 3)
+        );
+        assert_eq!((err.value, err.expected), (Some(91124), Some(65535)));
+        let err = decode("BB8ZZ").unwrap_err();
+        assert_eq!(
+            (err.kind, err.position, err.len),
+            (ErrorKind::TailOverflow, 3, 2)
+        );
+        assert_eq!((err.value, err.expected), (Some(1610), Some(255)));
+        assert_eq!(
+            decode("BB8:::").unwrap_err().to_string(),
+            "3-character group at index 3 decodes to 91124, above 65535: BB8{:::}"
+        );
+        assert_eq!(decode("BB8A"), Err(Base45Error::dangling(3)));
+        assert_eq!(decoded_len("BB8A"), Err(Base45Error::dangling(3)));
+        assert!(matches!(
+            crate::decode_iter("BB8QEDZZ".chars()).last(),
+            Some(Err(DecodeError {
+                kind: ErrorKind::TailOverflow,
+                position: 6,
+                ..
+            }))
+        ));
     }
 }
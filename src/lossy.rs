@@ -0,0 +1,70 @@
+//! Best-effort decoding that salvages what it can from damaged input.
+
+use alloc::vec::Vec;
+use core::ops::Range;
+
+use crate::{Base45Error, char_err, decode_tail, decode_triple};
+
+/// Result of [`decode_lossy`]: the salvaged bytes and where decoding failed.
+#[derive(Debug, Default)]
+pub struct LossyDecoded {
+    /// Decoded bytes, with filler (or nothing) in place of each failed group.
+    pub bytes: Vec<u8>,
+    /// Byte range in the input of each failed group, with the reason it failed.
+    pub errors: Vec<(Range<usize>, Base45Error)>,
+}
+
+/// Decode `s` group by group, never failing as a whole.
+///
+/// A group that cannot be decoded is replaced by `fill` (2 bytes for a full group, 1 for a
+/// final pair) or, with `None`, dropped. A dangling final character never produces output.
+pub fn decode_lossy(s: &str, fill: Option<u8>) -> LossyDecoded {
+    let bytes = s.as_bytes();
+    let mut out = LossyDecoded {
+        bytes: Vec::with_capacity(bytes.len() / 3 * 2 + 1),
+        errors: Vec::new(),
+    };
+    for (k, group) in bytes.chunks(3).enumerate() {
+        let at = k * 3;
+        let res = match group.len() {
+            3 => decode_triple(group, at).map(|pair| out.bytes.extend_from_slice(&pair)),
+            _ => decode_tail(group, at).map(|b| out.bytes.push(b)),
+        };
+        if let Err(e) = res {
+            if let (Some(b), 2..) = (fill, group.len()) {
+                out.bytes.extend(core::iter::repeat_n(b, group.len() - 1));
+            }
+            out.errors.push((at..at + group.len(), char_err(bytes)(e)));
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ErrorKind;
+
+    #[test]
+    fn clean_input_is_exact() {
+        let out = decode_lossy("%69 VD92EX0", Some(b'?'));
+        assert_eq!(out.bytes, b"Hello!!");
+        assert!(out.errors.is_empty());
+    }
+
+    #[test]
+    fn salvages_damaged_groups() {
+        // ":::" overflows (2 filler bytes), the final pair "Xb" has an invalid char (1 filler byte)
+        let filled = decode_lossy("BB8:::BB8QED8WEXb", Some(b'?'));
+        assert_eq!(filled.bytes, b"AB??ABietf?");
+        let spans: Vec<_> = filled.errors.iter().map(|(r, _)| r.clone()).collect();
+        assert_eq!(spans, vec![3..6, 15..17]);
+        assert_eq!(filled.errors[0].1.kind, ErrorKind::Overflow);
+        assert_eq!(filled.errors[1].1.kind, ErrorKind::InvalidChar);
+
+        let skipped = decode_lossy("BB8:::BB8A", None);
+        assert_eq!(skipped.bytes, b"ABAB");
+        let kinds: Vec<_> = skipped.errors.iter().map(|(_, e)| e.kind).collect();
+        assert_eq!(kinds, [ErrorKind::Overflow, ErrorKind::Dangling]);
+    }
+}
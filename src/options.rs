@@ -0,0 +1,349 @@
+//! Configurable encoding for human-facing layouts, and decoding for inputs that need
+//! normalising before strict RFC 9285 rules apply.
+
+use alloc::string::String;
+use alloc::vec::Vec;
+
+use crate::{Base45Error, ErrorKind, b45_val, char_err, decode_tail, decode_triple, encode};
+
+/// Builder for encoded output laid out for people: wrapped lines and/or grouped characters.
+///
+/// ```
+/// use qr_base45::{DecodeOptions, EncodeOptions};
+///
+/// let text = EncodeOptions::new().group(4, '_').wrap(10).encode(b"Hello!!");
+/// assert_eq!(text, "%69 _VD92_\nEX0");
+/// let opts = DecodeOptions::new().ignore_whitespace(true).ignore_separator(b'_');
+/// assert_eq!(opts.decode(&text).unwrap(), b"Hello!!");
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct EncodeOptions {
+    wrap: Option<usize>,
+    group: Option<(usize, u8)>,
+}
+
+impl EncodeOptions {
+    /// Plain output; equivalent to [`encode`](crate::encode).
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Insert `\n` after every `columns` output characters (separators included).
+    /// `0` disables wrapping.
+    pub fn wrap(mut self, columns: usize) -> Self {
+        self.wrap = (columns > 0).then_some(columns);
+        self
+    }
+
+    /// Insert `sep` after every `every` Base45 characters. `0` disables grouping.
+    ///
+    /// # Panics
+    /// If `sep` is not ASCII or is a Base45 character (such as `-` or space), since it could
+    /// not be told apart from the data when decoding.
+    pub fn group(mut self, every: usize, sep: char) -> Self {
+        let sep = u8::try_from(sep)
+            .ok()
+            .filter(|&b| b.is_ascii() && b45_val(b).is_none())
+            .expect("group separator must be an ASCII character outside the Base45 alphabet");
+        self.group = (every > 0).then_some((every, sep));
+        self
+    }
+
+    /// Encode `input` with this layout.
+    pub fn encode<T: AsRef<[u8]>>(&self, input: T) -> String {
+        let plain = encode(input);
+        if self.wrap.is_none() && self.group.is_none() {
+            return plain;
+        }
+        let mut out = String::with_capacity(plain.len() * 2);
+        let mut column = 0;
+        let mut push = |out: &mut String, ch: char| {
+            if self.wrap.is_some_and(|w| column == w) {
+                out.push('\n');
+                column = 0;
+            }
+            out.push(ch);
+            column += 1;
+        };
+        for (i, ch) in plain.chars().enumerate() {
+            if let Some((every, sep)) = self.group {
+                if i > 0 && i % every == 0 {
+                    push(&mut out, sep as char);
+                }
+            }
+            push(&mut out, ch);
+        }
+        out
+    }
+}
+
+/// Builder for a decoder with relaxed input handling.
+///
+/// The default configuration is identical to [`decode`](crate::decode): strict RFC 9285.
+///
+/// ```
+/// use qr_base45::DecodeOptions;
+///
+/// let opts = DecodeOptions::new()
+///     .ignore_whitespace(true)
+///     .accept_lowercase(true)
+///     .strip_prefix("HC1:")
+///     .max_output_len(1024);
+/// assert_eq!(opts.decode("HC1:qed8\nwex0").unwrap(), b"ietf!");
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct DecodeOptions {
+    ignore_whitespace: bool,
+    accept_lowercase: bool,
+    prefix: Option<String>,
+    require_prefix: bool,
+    separator: Option<u8>,
+    max_output_len: Option<usize>,
+}
+
+impl DecodeOptions {
+    /// Strict RFC 9285 decoding; equivalent to `DecodeOptions::default()`.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Skip tabs, line feeds, form feeds and carriage returns anywhere in the input.
+    ///
+    /// The space character is part of the Base45 alphabet and is never skipped.
+    pub fn ignore_whitespace(mut self, yes: bool) -> Self {
+        self.ignore_whitespace = yes;
+        self
+    }
+
+    /// Skip every occurrence of the ASCII character `sep`, e.g. the separator inserted by
+    /// [`EncodeOptions::group`]. Has no effect if `sep` is a Base45 character.
+    pub fn ignore_separator(mut self, sep: u8) -> Self {
+        self.separator = (b45_val(sep).is_none()).then_some(sep);
+        self
+    }
+
+    /// Map `a-z` to `A-Z` before alphabet lookup.
+    pub fn accept_lowercase(mut self, yes: bool) -> Self {
+        self.accept_lowercase = yes;
+        self
+    }
+
+    /// Remove `prefix` from the start of the input when present.
+    pub fn strip_prefix(mut self, prefix: impl Into<String>) -> Self {
+        self.prefix = Some(prefix.into());
+        self.require_prefix = false;
+        self
+    }
+
+    /// Require the input to start with `prefix` and remove it; fails with `MissingPrefix` otherwise.
+    pub fn require_prefix(mut self, prefix: impl Into<String>) -> Self {
+        self.prefix = Some(prefix.into());
+        self.require_prefix = true;
+        self
+    }
+
+    /// Reject inputs that would decode to more than `max` bytes with `TooLarge`, before allocating.
+    pub fn max_output_len(mut self, max: usize) -> Self {
+        self.max_output_len = Some(max);
+        self
+    }
+
+    /// Decode `s` using these options.
+    pub fn decode(&self, s: &str) -> Result<Vec<u8>, Base45Error> {
+        let body = self.body(s)?;
+        let n = self.significant_len(body);
+        let len = n / 3 * 2 + n % 3 / 2;
+        if self.max_output_len.is_some_and(|max| len > max) {
+            return Err(Base45Error::new(ErrorKind::TooLarge));
+        }
+        let mut out = Vec::with_capacity(len);
+        // Report positions relative to `s`, not to the body after the prefix
+        self.decode_body(body, s.len() - body.len(), &mut out)
+            .map_err(char_err(s.as_bytes()))?;
+        Ok(out)
+    }
+
+    fn body<'s>(&self, s: &'s str) -> Result<&'s str, Base45Error> {
+        match &self.prefix {
+            Some(p) => match s.strip_prefix(p.as_str()) {
+                Some(body) => Ok(body),
+                None if self.require_prefix => Err(Base45Error::new(ErrorKind::MissingPrefix)),
+                None => Ok(s),
+            },
+            None => Ok(s),
+        }
+    }
+
+    #[inline]
+    fn skips(&self, b: u8) -> bool {
+        (self.ignore_whitespace && b != b' ' && b.is_ascii_whitespace())
+            || self.separator == Some(b)
+    }
+
+    /// Number of input bytes that take part in decoding.
+    fn significant_len(&self, body: &str) -> usize {
+        if self.ignore_whitespace || self.separator.is_some() {
+            body.bytes().filter(|&b| !self.skips(b)).count()
+        } else {
+            body.len()
+        }
+    }
+
+    fn decode_body(&self, body: &str, offset: usize, out: &mut Vec<u8>) -> Result<(), Base45Error> {
+        let mut group = [0u8; 3];
+        let mut n = 0;
+        let mut start = 0;
+        for (i, &byte) in body.as_bytes().iter().enumerate() {
+            let i = offset + i;
+            if self.skips(byte) {
+                continue;
+            }
+            let b = if self.accept_lowercase {
+                byte.to_ascii_uppercase()
+            } else {
+                byte
+            };
+            // Check each char as it arrives so the reported index is its position in the input
+            if b45_val(b).is_none() {
+                return Err(Base45Error::invalid_byte(byte, i));
+            }
+            if n == 0 {
+                start = i;
+            }
+            group[n] = b;
+            n += 1;
+            if n == 3 {
+                out.extend_from_slice(&decode_triple(&group, start)?);
+                n = 0;
+            }
+        }
+        if n > 0 {
+            out.push(decode_tail(&group[..n], start)?);
+        }
+        Ok(())
+    }
+}
+
+/// Decode text that may have been wrapped or reflowed, skipping tabs and line breaks.
+///
+/// Shorthand for `DecodeOptions::new().ignore_whitespace(true).decode(s)`. Spaces are Base45 data,
+/// so stray trailing spaces are decoded (and usually fail) rather than dropped; every other
+/// character outside the alphabet is still rejected.
+pub fn decode_lenient(s: &str) -> Result<Vec<u8>, Base45Error> {
+    DecodeOptions::new().ignore_whitespace(true).decode(s)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn default_is_strict() {
+        let opts = DecodeOptions::new();
+        assert_eq!(opts.decode("QED8WEX0").unwrap(), b"ietf!");
+        assert!(matches!(
+            opts.decode("qed8wex0"),
+            Err(e) if e.kind == ErrorKind::InvalidChar
+        ));
+        assert!(matches!(
+            opts.decode("QED\n8WEX0"),
+            Err(e) if e.kind == ErrorKind::InvalidChar
+        ));
+        assert!(matches!(
+            opts.decode("BB8A"),
+            Err(e) if e.kind == ErrorKind::Dangling
+        ));
+    }
+
+    #[test]
+    fn relaxed_toggles() {
+        let ws = DecodeOptions::new().ignore_whitespace(true);
+        assert_eq!(ws.decode("QE\r\nD8W\tEX0\n").unwrap(), b"ietf!");
+        // Space is an alphabet character, not whitespace to skip
+        assert_eq!(ws.decode("%69 VD92EX0").unwrap(), b"Hello!!");
+
+        let lower = DecodeOptions::new().accept_lowercase(true);
+        assert_eq!(lower.decode("qEd8wEx0").unwrap(), b"ietf!");
+        // Only ASCII letters are folded; everything else is as strict as before
+        assert!(matches!(
+            lower.decode("bb8~"),
+            Err(e) if e.kind == ErrorKind::InvalidChar
+        ));
+        assert!(matches!(
+            lower.decode("zz"),
+            Err(e) if e.kind == ErrorKind::TailOverflow
+        ));
+        assert!(matches!(
+            lower.decode("\u{212A}B8"),
+            Err(e) if e.kind == ErrorKind::InvalidChar
+        )); // Kelvin sign
+
+        let prefixed = DecodeOptions::new().strip_prefix("HC1:");
+        assert_eq!(prefixed.decode("HC1:BB8").unwrap(), b"AB");
+        assert_eq!(prefixed.decode("BB8").unwrap(), b"AB");
+        let required = DecodeOptions::new().require_prefix("HC1:");
+        assert_eq!(required.decode("HC1:BB8").unwrap(), b"AB");
+        assert!(matches!(
+            required.decode("BB8"),
+            Err(e) if e.kind == ErrorKind::MissingPrefix
+        ));
+    }
+
+    #[test]
+    fn lenient_skips_line_breaks() {
+        let wrapped = "%69 VD\r\n92EX0\n";
+        assert_eq!(decode_lenient(wrapped).unwrap(), b"Hello!!");
+        assert!(matches!(
+            decode_lenient("BB8\n-!"),
+            Err(e) if e.kind == ErrorKind::InvalidChar
+        ));
+        assert!(matches!(
+            decode_lenient("BB8\nA\n"),
+            Err(e) if e.kind == ErrorKind::Dangling
+        ));
+    }
+
+    #[test]
+    fn encode_layouts() {
+        let data: Vec<u8> = (0..40).collect();
+        let plain = crate::encode(&data);
+        assert_eq!(EncodeOptions::new().encode(&data), plain);
+
+        let wrapped = EncodeOptions::new().wrap(16).encode(&data);
+        assert!(wrapped.lines().all(|l| l.len() <= 16));
+        assert_eq!(wrapped.replace('\n', ""), plain);
+
+        let grouped = EncodeOptions::new().group(5, '|').encode(&data);
+        assert!(grouped.split('|').all(|g| g.len() <= 5));
+        let opts = DecodeOptions::new().ignore_separator(b'|');
+        assert_eq!(opts.decode(&grouped).unwrap(), data);
+        // Without the option the separator is an invalid character
+        assert!(matches!(
+            crate::decode(&grouped),
+            Err(e) if e.kind == ErrorKind::InvalidChar
+        ));
+    }
+
+    #[test]
+    #[should_panic(expected = "outside the Base45 alphabet")]
+    fn alphabet_separator_rejected() {
+        let _ = EncodeOptions::new().group(4, '-');
+    }
+
+    #[test]
+    fn output_cap() {
+        let capped = DecodeOptions::new()
+            .max_output_len(5)
+            .ignore_whitespace(true);
+        assert_eq!(capped.decode("QED8WEX0\n\n").unwrap(), b"ietf!");
+        assert!(matches!(
+            capped.decode("QED8WEX0BB8"),
+            Err(e) if e.kind == ErrorKind::TooLarge
+        ));
+        // The cap is checked before anything is decoded
+        assert!(matches!(
+            capped.decode("~~~~~~~~~~~~"),
+            Err(e) if e.kind == ErrorKind::TooLarge
+        ));
+    }
+}
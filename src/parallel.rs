@@ -0,0 +1,117 @@
+//! Multi-threaded encoding and decoding of large buffers on the rayon thread pool.
+
+use alloc::string::String;
+use alloc::vec::Vec;
+
+use rayon::prelude::*;
+
+use crate::{Base45Error, ascii_string, char_err, decode_exact, encode_exact, encoded_len};
+
+/// Encode `input` like [`encode`](crate::encode), splitting it into chunks of `chunk_size` bytes
+/// that are encoded in parallel on the rayon thread pool. Groups are independent, so the output
+/// is identical to `encode(input)`.
+///
+/// `chunk_size` is rounded down to an even number, at least 2, so no group straddles two chunks.
+/// Each chunk is one rayon task: a few hundred KiB or more keeps scheduling overhead negligible.
+///
+/// ```
+/// let data = vec![0x5A; 1 << 20];
+/// assert_eq!(qr_base45::encode_parallel(&data, 64 * 1024), qr_base45::encode(&data));
+/// ```
+pub fn encode_parallel<T: AsRef<[u8]>>(input: T, chunk_size: usize) -> String {
+    let input = input.as_ref();
+    // Clamped to the input first, so the output chunk size below cannot overflow
+    let chunk_size = (chunk_size.min(input.len().max(2)) & !1).max(2);
+    let mut out = alloc::vec![0; encoded_len(input.len())];
+    input
+        .par_chunks(chunk_size)
+        .zip(out.par_chunks_mut(chunk_size / 2 * 3))
+        .for_each(|(input, out)| encode_exact(input, out));
+    ascii_string(out)
+}
+
+/// Decode `s` like [`decode`](crate::decode), splitting it into chunks of `chunk_size`
+/// characters that are decoded in parallel on the rayon thread pool and written to the output in
+/// order.
+///
+/// `chunk_size` is rounded down to a multiple of 3, at least 3, so no group straddles two
+/// chunks. On invalid input the error is the one `decode` reports: the earliest in the text,
+/// with its position in `s`.
+///
+/// ```
+/// let text = qr_base45::encode(vec![0x5A; 1 << 20]);
+/// assert_eq!(qr_base45::decode_parallel(&text, 96 * 1024).unwrap(), vec![0x5A; 1 << 20]);
+/// ```
+pub fn decode_parallel(s: &str, chunk_size: usize) -> Result<Vec<u8>, Base45Error> {
+    let bytes = s.as_bytes();
+    let chunk_size = (chunk_size / 3 * 3).max(3);
+    let mut out = alloc::vec![0; bytes.len() / 3 * 2 + bytes.len() % 3 / 2];
+    // Whole groups in parallel; a final partial group has no fixed share of the output
+    let (groups, tail) = bytes.split_at(bytes.len() / 3 * 3);
+    let (out_groups, out_tail) = out.split_at_mut(groups.len() / 3 * 2);
+    let first_err = groups
+        .par_chunks(chunk_size)
+        .zip(out_groups.par_chunks_mut(chunk_size / 3 * 2))
+        .enumerate()
+        .find_map_first(|(i, (chunk, out))| {
+            let mut e = decode_exact(chunk, out).err()?;
+            e.position += i * chunk_size;
+            Some(e)
+        });
+    if let Some(e) = first_err {
+        return Err(char_err(bytes)(e));
+    }
+    decode_exact(tail, out_tail).map_err(|mut e| {
+        e.position += groups.len();
+        char_err(bytes)(e)
+    })?;
+    Ok(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn encode_matches_sequential_encode() {
+        let data: Vec<u8> = (0..10_001u32).map(|i| (i * 167 + 13) as u8).collect();
+        for len in [0, 1, 2, 3, 999, 10_000, 10_001] {
+            for chunk_size in [0, 1, 2, 3, 64, 1000, 1001, 1 << 20, usize::MAX] {
+                assert_eq!(
+                    encode_parallel(&data[..len], chunk_size),
+                    crate::encode(&data[..len]),
+                    "len {len}, chunk_size {chunk_size}"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn decode_matches_sequential_decode() {
+        let data: Vec<u8> = (0..10_001u32).map(|i| (i * 167 + 13) as u8).collect();
+        for len in [0, 1, 2, 3, 999, 10_000, 10_001] {
+            let text = crate::encode(&data[..len]);
+            for chunk_size in [0, 1, 3, 4, 64, 1000, 1002, 1 << 20] {
+                assert_eq!(decode_parallel(&text, chunk_size).unwrap(), data[..len]);
+            }
+        }
+    }
+
+    #[test]
+    fn decode_reports_the_earliest_error() {
+        let mut text = crate::encode([0x5A; 1000]);
+        text.replace_range(1200..1203, ":::");
+        text.replace_range(300..301, "é");
+        text.push('0');
+        let expected = crate::decode(&text).unwrap_err();
+        assert_eq!(expected.position, 300);
+        for chunk_size in [3, 30, 300, 1500, 3000] {
+            let err = decode_parallel(&text, chunk_size).unwrap_err();
+            assert_eq!(err, expected);
+            assert_eq!(err.to_string(), expected.to_string());
+        }
+        // Structural errors after the last whole group keep their absolute position
+        let err = decode_parallel("BB8A", 3).unwrap_err();
+        assert_eq!(err, crate::decode("BB8A").unwrap_err());
+    }
+}
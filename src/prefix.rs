@@ -0,0 +1,48 @@
+//! Helpers for Base45 bodies behind a fixed context prefix, such as `HC1:` in EU health certificates.
+
+use alloc::string::String;
+use alloc::vec::Vec;
+
+use crate::{Base45Error, DecodeOptions, encode_into};
+
+/// Encode `input` and prepend `prefix`, e.g. `encode_with_prefix("HC1:", &cbor)`.
+pub fn encode_with_prefix<T: AsRef<[u8]>>(prefix: &str, input: T) -> String {
+    let input = input.as_ref();
+    let mut out = String::with_capacity(prefix.len() + crate::encoded_len(input.len()));
+    out.push_str(prefix);
+    encode_into(input, &mut out);
+    out
+}
+
+/// Verify that `s` starts with `prefix`, strip it, and strictly decode the rest.
+/// Returns `MissingPrefix` if `s` does not start with `prefix` (the match is case-sensitive).
+pub fn decode_with_prefix(prefix: &str, s: &str) -> Result<Vec<u8>, Base45Error> {
+    DecodeOptions::new().require_prefix(prefix).decode(s)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ErrorKind;
+
+    #[test]
+    fn prefix_roundtrip() {
+        let s = encode_with_prefix("HC1:", b"ietf!");
+        assert_eq!(s, "HC1:QED8WEX0");
+        assert_eq!(decode_with_prefix("HC1:", &s).unwrap(), b"ietf!");
+
+        assert!(matches!(
+            decode_with_prefix("HC1:", "QED8WEX0"),
+            Err(e) if e.kind == ErrorKind::MissingPrefix
+        ));
+        assert!(matches!(
+            decode_with_prefix("HC1:", "hc1:QED8WEX0"),
+            Err(e) if e.kind == ErrorKind::MissingPrefix
+        ));
+        // The body is still decoded strictly
+        assert!(matches!(
+            decode_with_prefix("HC1:", "HC1:BB8A"),
+            Err(e) if e.kind == ErrorKind::Dangling
+        ));
+    }
+}
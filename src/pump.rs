@@ -0,0 +1,379 @@
+//! Poll-agnostic buffering shared by the async adapters: input waits in one fixed buffer until it
+//! holds whole groups, their conversion waits in another until the inner reader or writer takes
+//! it. The adapters only supply the inner `poll_read` or `poll_write`.
+
+use core::task::{Poll, ready};
+use std::io;
+
+use crate::{Base45Error, char_err_at, decode_exact, encode_exact, encoded_len};
+
+/// Number of 3-character groups converted per step.
+const GROUPS_PER_CHUNK: usize = 256;
+/// Size of each buffer: a chunk of text, and a chunk and a half of raw bytes.
+const BUF: usize = GROUPS_PER_CHUNK * 3;
+
+#[derive(Debug)]
+pub(crate) struct Pump {
+    decode: bool,
+    /// Input not yet converted: `input[..input_len]`.
+    input: [u8; BUF],
+    input_len: usize,
+    /// Converted output not yet handed on: `out[pos..len]`.
+    out: [u8; BUF],
+    pos: usize,
+    len: usize,
+    /// Stream offset of `input[0]`.
+    at: usize,
+    /// The input has ended: a partial group is the final one.
+    end: bool,
+    error: Option<Base45Error>,
+}
+
+impl Pump {
+    pub(crate) const fn encoder() -> Pump {
+        Pump::new(false)
+    }
+
+    pub(crate) const fn decoder() -> Pump {
+        Pump::new(true)
+    }
+
+    const fn new(decode: bool) -> Pump {
+        Pump {
+            decode,
+            input: [0; BUF],
+            input_len: 0,
+            out: [0; BUF],
+            pos: 0,
+            len: 0,
+            at: 0,
+            end: false,
+            error: None,
+        }
+    }
+
+    /// Convert whole groups of input, or the final partial group once the input has ended, into
+    /// the empty output buffer.
+    fn convert(&mut self) -> io::Result<()> {
+        if let Some(e) = self.error {
+            return Err(e.into());
+        }
+        let (n, len) = if self.decode {
+            let n = match self.input_len {
+                n if n < 3 && self.end => n,
+                n => n / 3 * 3,
+            };
+            let text = &self.input[..n];
+            let len = n / 3 * 2 + n % 3 / 2;
+            if let Err(mut e) = decode_exact(text, &mut self.out[..len]) {
+                e.position += self.at;
+                let e = char_err_at(text, self.at)(e);
+                self.error = Some(e);
+                return Err(e.into());
+            }
+            (n, len)
+        } else {
+            let n = match self.input_len {
+                1 if self.end => 1,
+                n => n.min(GROUPS_PER_CHUNK * 2) / 2 * 2,
+            };
+            let len = encoded_len(n);
+            encode_exact(&self.input[..n], &mut self.out[..len]);
+            (n, len)
+        };
+        (self.pos, self.len) = (0, len);
+        self.input.copy_within(n..self.input_len, 0);
+        self.input_len -= n;
+        self.at += n;
+        Ok(())
+    }
+
+    /// Fill `buf` with output, converting input taken from `read` as needed. Returns 0 only at the
+    /// end of the stream.
+    pub(crate) fn poll_read(
+        &mut self,
+        mut read: impl FnMut(&mut [u8]) -> Poll<io::Result<usize>>,
+        buf: &mut [u8],
+    ) -> Poll<io::Result<usize>> {
+        if buf.is_empty() {
+            return Poll::Ready(Ok(0));
+        }
+        loop {
+            if self.pos < self.len {
+                let n = buf.len().min(self.len - self.pos);
+                buf[..n].copy_from_slice(&self.out[self.pos..self.pos + n]);
+                self.pos += n;
+                return Poll::Ready(Ok(n));
+            }
+            self.convert()?;
+            if self.pos < self.len {
+                continue;
+            }
+            if self.end {
+                return Poll::Ready(Ok(0));
+            }
+            // Nothing left to convert, so the input holds at most a partial group
+            match ready!(read(&mut self.input[self.input_len..]))? {
+                0 => self.end = true,
+                n => self.input_len += n,
+            }
+        }
+    }
+
+    /// Take as much of `buf` as fits, once earlier output has gone to `write`. Returns the number
+    /// of bytes taken, never 0 for a non-empty `buf`.
+    pub(crate) fn poll_write(
+        &mut self,
+        write: impl FnMut(&[u8]) -> Poll<io::Result<usize>>,
+        buf: &[u8],
+    ) -> Poll<io::Result<usize>> {
+        ready!(self.poll_drain(write))?;
+        if let Some(e) = self.error {
+            return Poll::Ready(Err(e.into()));
+        }
+        // Conversion leaves at most a chunk of input behind, so there is always room
+        let n = buf.len().min(BUF - self.input_len);
+        self.input[self.input_len..self.input_len + n].copy_from_slice(&buf[..n]);
+        self.input_len += n;
+        self.convert()?;
+        Poll::Ready(Ok(n))
+    }
+
+    /// Hand all pending output to `write`.
+    pub(crate) fn poll_drain(
+        &mut self,
+        mut write: impl FnMut(&[u8]) -> Poll<io::Result<usize>>,
+    ) -> Poll<io::Result<()>> {
+        while self.pos < self.len {
+            match ready!(write(&self.out[self.pos..self.len]))? {
+                0 => return Poll::Ready(Err(io::ErrorKind::WriteZero.into())),
+                n => self.pos += n,
+            }
+        }
+        Poll::Ready(Ok(()))
+    }
+
+    /// End the input and hand everything left, the final partial group included, to `write`.
+    pub(crate) fn poll_finish(
+        &mut self,
+        mut write: impl FnMut(&[u8]) -> Poll<io::Result<usize>>,
+    ) -> Poll<io::Result<()>> {
+        loop {
+            ready!(self.poll_drain(&mut write))?;
+            if self.end && self.input_len == 0 {
+                if let Some(e) = self.error {
+                    return Poll::Ready(Err(e.into()));
+                }
+                return Poll::Ready(Ok(()));
+            }
+            self.end = true;
+            self.convert()?;
+        }
+    }
+}
+
+/// Define an adapter type around an inner `reader` or `writer` and a [`Pump`] made by
+/// `Pump::$pump`, with its constructor and accessors. Each async module implements the traits.
+macro_rules! adapter {
+    (reader, $(#[$doc:meta])* $name:ident, $pump:ident) => {
+        $(#[$doc])*
+        #[derive(Debug)]
+        pub struct $name<R> {
+            inner: R,
+            pump: Pump,
+        }
+
+        impl<R> $name<R> {
+            /// Wrap `inner`.
+            pub fn new(inner: R) -> Self {
+                $name {
+                    inner,
+                    pump: Pump::$pump(),
+                }
+            }
+
+            /// The inner reader.
+            pub fn get_ref(&self) -> &R {
+                &self.inner
+            }
+
+            /// The inner reader. Reading from it directly skips that input.
+            pub fn get_mut(&mut self) -> &mut R {
+                &mut self.inner
+            }
+
+            /// Unwrap the inner reader, dropping any buffered input and output.
+            pub fn into_inner(self) -> R {
+                self.inner
+            }
+        }
+    };
+    (writer, $(#[$doc:meta])* $name:ident, $pump:ident) => {
+        $(#[$doc])*
+        #[derive(Debug)]
+        pub struct $name<W> {
+            inner: W,
+            pump: Pump,
+        }
+
+        impl<W> $name<W> {
+            /// Wrap `inner`.
+            pub fn new(inner: W) -> Self {
+                $name {
+                    inner,
+                    pump: Pump::$pump(),
+                }
+            }
+
+            /// The inner writer.
+            pub fn get_ref(&self) -> &W {
+                &self.inner
+            }
+
+            /// The inner writer. Writing to it directly interleaves with the adapter's output.
+            pub fn get_mut(&mut self) -> &mut W {
+                &mut self.inner
+            }
+
+            /// Unwrap the inner writer, dropping any output not yet written.
+            pub fn into_inner(self) -> W {
+                self.inner
+            }
+        }
+    };
+}
+pub(crate) use adapter;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ErrorKind;
+
+    /// Stands in for the inner reader or writer: returns `Pending` on every other call and moves
+    /// at most `step` bytes per call, splitting groups at every offset.
+    struct Trickle {
+        step: usize,
+        ready: bool,
+    }
+
+    impl Trickle {
+        fn new(step: usize) -> Self {
+            Trickle { step, ready: false }
+        }
+
+        /// How many of `n` bytes this call moves, or `None` if it is pending.
+        fn turn(&mut self, n: usize) -> Option<usize> {
+            self.ready = !self.ready;
+            self.ready.then_some(n.min(self.step))
+        }
+
+        /// Read all of the pump's output, with its input read from `src`.
+        fn read(&mut self, pump: &mut Pump, mut src: &[u8]) -> io::Result<Vec<u8>> {
+            let mut out = Vec::new();
+            let mut buf = vec![0; self.step];
+            loop {
+                let read = |dst: &mut [u8]| match self.turn(dst.len().min(src.len())) {
+                    Some(n) => {
+                        dst[..n].copy_from_slice(&src[..n]);
+                        src = &src[n..];
+                        Poll::Ready(Ok(n))
+                    }
+                    None => Poll::Pending,
+                };
+                match pump.poll_read(read, &mut buf) {
+                    Poll::Ready(Ok(0)) => return Ok(out),
+                    Poll::Ready(Ok(n)) => out.extend_from_slice(&buf[..n]),
+                    Poll::Ready(Err(e)) => return Err(e),
+                    Poll::Pending => {}
+                }
+            }
+        }
+
+        /// The inner `poll_write`, appending to `out`.
+        fn sink<'a>(
+            &'a mut self,
+            out: &'a mut Vec<u8>,
+        ) -> impl FnMut(&[u8]) -> Poll<io::Result<usize>> + 'a {
+            |src| match self.turn(src.len()) {
+                Some(n) => {
+                    out.extend_from_slice(&src[..n]);
+                    Poll::Ready(Ok(n))
+                }
+                None => Poll::Pending,
+            }
+        }
+
+        /// Write all of `src` to the pump, a chunk of `step + 1` bytes at a time.
+        fn write(&mut self, pump: &mut Pump, src: &[u8], out: &mut Vec<u8>) -> io::Result<()> {
+            for mut chunk in src.chunks(self.step + 1) {
+                while !chunk.is_empty() {
+                    if let Poll::Ready(n) = pump.poll_write(self.sink(out), chunk) {
+                        chunk = &chunk[n?..];
+                    }
+                }
+            }
+            Ok(())
+        }
+
+        fn finish(&mut self, pump: &mut Pump, out: &mut Vec<u8>) -> io::Result<()> {
+            loop {
+                if let Poll::Ready(r) = pump.poll_finish(self.sink(out)) {
+                    return r;
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn pumps_match_one_shot_calls() {
+        let data: Vec<u8> = (0..3001u32).map(|i| (i * 167 + 13) as u8).collect();
+        let text = crate::encode(&data);
+        for step in [1, 2, 3, 7, 1000] {
+            let mut t = Trickle::new(step);
+            assert_eq!(
+                t.read(&mut Pump::encoder(), &data).unwrap(),
+                text.as_bytes()
+            );
+            assert_eq!(t.read(&mut Pump::decoder(), text.as_bytes()).unwrap(), data);
+
+            let (mut pump, mut out) = (Pump::encoder(), Vec::new());
+            t.write(&mut pump, &data, &mut out).unwrap();
+            t.finish(&mut pump, &mut out).unwrap();
+            assert_eq!(out, text.as_bytes());
+
+            let (mut pump, mut out) = (Pump::decoder(), Vec::new());
+            t.write(&mut pump, text.as_bytes(), &mut out).unwrap();
+            t.finish(&mut pump, &mut out).unwrap();
+            assert_eq!(out, data);
+        }
+    }
+
+    #[test]
+    fn decode_errors_are_sticky() {
+        let mut text = crate::encode([0x5A; 1000]).into_bytes();
+        text[1000] = b'a';
+        let base45 = |e: io::Error| {
+            assert_eq!(e.kind(), io::ErrorKind::InvalidData);
+            let e = e.into_inner().unwrap().downcast::<Base45Error>().unwrap();
+            (e.kind, e.position)
+        };
+        let mut t = Trickle::new(5);
+
+        let mut pump = Pump::decoder();
+        let err = t.read(&mut pump, &text).unwrap_err();
+        assert_eq!(base45(err), (ErrorKind::InvalidChar, 1000));
+        assert!(t.read(&mut pump, b"").is_err());
+
+        let (mut pump, mut out) = (Pump::decoder(), Vec::new());
+        let err = t.write(&mut pump, &text, &mut out).unwrap_err();
+        assert_eq!(base45(err), (ErrorKind::InvalidChar, 1000));
+        assert!(t.finish(&mut pump, &mut out).is_err());
+
+        // The trailing group is only checked at the end
+        let (mut pump, mut out) = (Pump::decoder(), Vec::new());
+        t.write(&mut pump, b"BB8A", &mut out).unwrap();
+        let err = t.finish(&mut pump, &mut out).unwrap_err();
+        assert_eq!(base45(err), (ErrorKind::Dangling, 3));
+        assert!(t.write(&mut pump, b"BB8", &mut out).is_err());
+    }
+}
@@ -0,0 +1,475 @@
+//! QR code capacity in alphanumeric mode, the mode Base45 text is designed for, and the smallest
+//! symbol that holds a payload.
+//!
+//! ```
+//! use qr_base45::qr::{EccLevel, alphanumeric_capacity, byte_capacity, qr_version_for};
+//!
+//! // 100 bytes encode to 150 characters
+//! assert_eq!(qr_version_for(100, EccLevel::M), Some(6));
+//! assert_eq!(alphanumeric_capacity(6, EccLevel::M), Some(154));
+//! assert_eq!(byte_capacity(6, EccLevel::M), Some(102));
+//! assert_eq!(qr_version_for(3000, EccLevel::L), None);
+//! ```
+//!
+//! Payloads too large for one code can be split across several with `chunk_for_qr`, or with
+//! `split_for_qr`, which prefixes each part with an `index/total:` header in decimal so a
+//! `Reassembler` can collect the parts in any order:
+//!
+//! ```
+//! use qr_base45::qr::{EccLevel, Reassembler, split_for_qr};
+//!
+//! let data = [0x5A; 40];
+//! let parts = split_for_qr(&data, 1, EccLevel::L).unwrap();
+//! assert!(parts[0].starts_with("0/3:"));
+//!
+//! let mut rx = Reassembler::new();
+//! for part in parts.iter().rev() {
+//!     rx.push(part).unwrap();
+//! }
+//! assert_eq!(rx.finish().unwrap(), data);
+//! ```
+
+#[cfg(feature = "alloc")]
+use alloc::{format, string::String, vec::Vec};
+
+#[cfg(feature = "alloc")]
+use crate::{Base45Error, ErrorKind, char_err_at, encode, encode_into};
+
+/// Smallest QR version.
+pub const MIN_VERSION: u8 = 1;
+/// Largest QR version.
+pub const MAX_VERSION: u8 = 40;
+
+/// Error correction level: how much of the symbol may be damaged and still read.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum EccLevel {
+    /// About 7% recoverable.
+    L,
+    /// About 15% recoverable.
+    #[default]
+    M,
+    /// About 25% recoverable.
+    Q,
+    /// About 30% recoverable.
+    H,
+}
+
+/// Alphanumeric characters each version holds at levels L, M, Q and H, indexed by
+/// `[version - 1][level as usize]` (ISO/IEC 18004 table 7).
+pub const ALPHANUMERIC_CAPACITY: [[u16; 4]; 40] = [
+    [25, 20, 16, 10],
+    [47, 38, 29, 20],
+    [77, 61, 47, 35],
+    [114, 90, 67, 50],
+    [154, 122, 87, 64],
+    [195, 154, 108, 84],
+    [224, 178, 125, 93],
+    [279, 221, 157, 122],
+    [335, 262, 189, 143],
+    [395, 311, 221, 174],
+    [468, 366, 259, 200],
+    [535, 419, 296, 227],
+    [619, 483, 352, 259],
+    [667, 528, 376, 283],
+    [758, 600, 426, 321],
+    [854, 656, 470, 365],
+    [938, 734, 531, 408],
+    [1046, 816, 574, 452],
+    [1153, 909, 644, 493],
+    [1249, 970, 702, 557],
+    [1352, 1035, 742, 587],
+    [1460, 1134, 823, 640],
+    [1588, 1248, 890, 672],
+    [1704, 1326, 963, 744],
+    [1853, 1451, 1041, 779],
+    [1990, 1542, 1094, 864],
+    [2132, 1637, 1172, 910],
+    [2223, 1732, 1263, 958],
+    [2369, 1839, 1322, 1016],
+    [2520, 1994, 1429, 1080],
+    [2677, 2113, 1499, 1150],
+    [2840, 2238, 1618, 1226],
+    [3009, 2369, 1700, 1307],
+    [3183, 2506, 1787, 1394],
+    [3351, 2632, 1867, 1431],
+    [3537, 2780, 1966, 1530],
+    [3729, 2894, 2071, 1591],
+    [3927, 3054, 2181, 1658],
+    [4087, 3220, 2298, 1774],
+    [4296, 3391, 2420, 1852],
+];
+
+/// Most parts [`split_for_qr`] produces and a [`Reassembler`] accepts, so `total` has at most
+/// four digits.
+pub const MAX_PARTS: usize = 9999;
+
+/// Alphanumeric characters a QR code of `version` holds at level `ecc`, or `None` if `version`
+/// is not in `1..=40`.
+pub const fn alphanumeric_capacity(version: u8, ecc: EccLevel) -> Option<usize> {
+    if version < MIN_VERSION || version > MAX_VERSION {
+        return None;
+    }
+    Some(ALPHANUMERIC_CAPACITY[version as usize - 1][ecc as usize] as usize)
+}
+
+/// Raw bytes whose Base45 encoding fits a QR code of `version` at level `ecc`, or `None` if
+/// `version` is not in `1..=40`.
+pub const fn byte_capacity(version: u8, ecc: EccLevel) -> Option<usize> {
+    match alphanumeric_capacity(version, ecc) {
+        Some(chars) => Some(bytes_in(chars)),
+        None => None,
+    }
+}
+
+/// Raw bytes whose encoding fits in `chars` characters: two per 3, one more if 2 are left over.
+const fn bytes_in(chars: usize) -> usize {
+    chars / 3 * 2 + chars % 3 / 2
+}
+
+/// The smallest QR version whose alphanumeric capacity at level `ecc` fits the Base45 encoding of
+/// `len` bytes, or `None` if not even version 40 does.
+pub const fn qr_version_for(len: usize, ecc: EccLevel) -> Option<u8> {
+    let mut version = MIN_VERSION;
+    while version <= MAX_VERSION {
+        match byte_capacity(version, ecc) {
+            Some(bytes) if len <= bytes => return Some(version),
+            _ => version += 1,
+        }
+    }
+    None
+}
+
+/// Split `bytes` into chunks whose Base45 encodings each fit a QR code of `version` at level `ecc`,
+/// and return the encodings.
+///
+/// Every chunk but the last holds the same even number of bytes, so the chunks encode whole groups
+/// and their concatenation is the encoding of `bytes`. An empty payload gives a single empty
+/// chunk. Returns `InvalidLength` if `version` is not in `1..=40`.
+///
+/// ```
+/// use qr_base45::qr::{EccLevel, chunk_for_qr};
+///
+/// let chunks = chunk_for_qr(&[0xA5; 40], 1, EccLevel::L).unwrap();
+/// assert_eq!(chunks.iter().map(String::len).collect::<Vec<_>>(), [24, 24, 12]);
+/// assert_eq!(chunks.concat(), qr_base45::encode([0xA5; 40]));
+/// ```
+#[cfg(feature = "alloc")]
+pub fn chunk_for_qr(bytes: &[u8], version: u8, ecc: EccLevel) -> Result<Vec<String>, Base45Error> {
+    let Some(capacity) = byte_capacity(version, ecc) else {
+        return Err(Base45Error::new(ErrorKind::InvalidLength));
+    };
+    if bytes.is_empty() {
+        return Ok(alloc::vec![String::new()]);
+    }
+    Ok(bytes.chunks(capacity & !1).map(encode).collect())
+}
+
+/// Split `bytes` into parts that each fit a QR code of `version` at level `ecc`, header included.
+/// Part `i` of `n` is `"{i}/{n}:"` followed by the Base45 encoding of its chunk; every chunk but
+/// the last holds the same even number of bytes.
+///
+/// Returns `InvalidLength` if `version` is not in `1..=40`, and `TooLarge` if the payload needs
+/// more than [`MAX_PARTS`] parts or a header leaves no room for data.
+#[cfg(feature = "alloc")]
+pub fn split_for_qr(bytes: &[u8], version: u8, ecc: EccLevel) -> Result<Vec<String>, Base45Error> {
+    let Some(capacity) = alphanumeric_capacity(version, ecc) else {
+        return Err(Base45Error::new(ErrorKind::InvalidLength));
+    };
+    // Size chunks for the longest header with `digits`-digit numbers, until the count fits
+    for digits in 1..=4 {
+        let Some(room) = capacity.checked_sub(2 * digits + 2) else {
+            break;
+        };
+        let chunk = bytes_in(room) & !1;
+        if chunk == 0 {
+            break;
+        }
+        let total = bytes.len().div_ceil(chunk).max(1);
+        if total.ilog10() as usize >= digits {
+            continue;
+        }
+        // An empty payload still makes one part
+        let chunks = bytes
+            .chunks(chunk)
+            .chain(bytes.is_empty().then_some(&[][..]));
+        let parts = chunks.enumerate().map(|(i, chunk)| {
+            let mut part = format!("{i}/{total}:");
+            encode_into(chunk, &mut part);
+            part
+        });
+        return Ok(parts.collect());
+    }
+    Err(Base45Error::new(ErrorKind::TooLarge))
+}
+
+/// Collects the parts made by [`split_for_qr`] in any order and decodes the payload once all of
+/// them have arrived.
+#[cfg(feature = "alloc")]
+#[derive(Debug, Clone, Default)]
+pub struct Reassembler {
+    /// Decoded chunks by index; the length is the part count, once a part has been seen.
+    parts: Vec<Option<Vec<u8>>>,
+    received: usize,
+}
+
+#[cfg(feature = "alloc")]
+impl Reassembler {
+    /// An empty reassembler.
+    pub fn new() -> Reassembler {
+        Reassembler::default()
+    }
+
+    /// Accept one part as scanned, returning `false` if it is a duplicate of one already received.
+    ///
+    /// Returns `InvalidFrame` for a malformed header, a part count that disagrees with earlier
+    /// parts, or a duplicate index with different content, and the decode error if the Base45
+    /// text is invalid, positioned in `part`. A rejected part leaves the state unchanged.
+    pub fn push(&mut self, part: &str) -> Result<bool, Base45Error> {
+        let invalid = || Base45Error::new(ErrorKind::InvalidFrame);
+        let (index, total, start) = parse_header(part).ok_or_else(invalid)?;
+        if !self.parts.is_empty() && self.parts.len() != total {
+            return Err(invalid());
+        }
+        let text = &part[start..];
+        let chunk = crate::decode(text).map_err(|mut e| {
+            e.position += start;
+            char_err_at(text.as_bytes(), start)(e)
+        })?;
+        if self.parts.is_empty() {
+            self.parts.resize(total, None);
+        }
+        match &self.parts[index] {
+            Some(seen) if *seen == chunk => Ok(false),
+            Some(_) => Err(invalid()),
+            None => {
+                self.parts[index] = Some(chunk);
+                self.received += 1;
+                Ok(true)
+            }
+        }
+    }
+
+    /// The part count, once a part has been received.
+    pub fn total(&self) -> Option<usize> {
+        Some(self.parts.len()).filter(|&n| n > 0)
+    }
+
+    /// Whether every part has arrived.
+    pub fn is_complete(&self) -> bool {
+        !self.parts.is_empty() && self.received == self.parts.len()
+    }
+
+    /// Indexes of the parts still missing, in order. Empty until a part has been received.
+    pub fn missing(&self) -> impl Iterator<Item = usize> + '_ {
+        self.parts
+            .iter()
+            .enumerate()
+            .filter(|(_, p)| p.is_none())
+            .map(|(i, _)| i)
+    }
+
+    /// The original bytes, once complete.
+    ///
+    /// Returns `InvalidFrame` if parts are still missing.
+    pub fn finish(self) -> Result<Vec<u8>, Base45Error> {
+        if !self.is_complete() {
+            return Err(Base45Error::new(ErrorKind::InvalidFrame));
+        }
+        Ok(self.parts.into_iter().flatten().flatten().collect())
+    }
+}
+
+/// Parse an `index/total:` header, returning the index, the count and where the text starts.
+#[cfg(feature = "alloc")]
+fn parse_header(part: &str) -> Option<(usize, usize, usize)> {
+    let (header, _) = part.split_once(':')?;
+    let (index, total) = header.split_once('/')?;
+    // `usize::from_str` takes a leading `+`, which is a Base45 character
+    let number = |s: &str| {
+        let digits = !s.is_empty() && s.len() <= 4 && s.bytes().all(|b| b.is_ascii_digit());
+        digits.then(|| s.parse::<usize>().ok()).flatten()
+    };
+    let (index, total) = (number(index)?, number(total)?);
+    (index < total).then_some((index, total, header.len() + 1))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::encoded_len;
+
+    #[test]
+    fn capacity_follows_the_data_codewords() {
+        // Data codewords per version and level; alphanumeric mode spends 4 mode bits and a
+        // 9, 11 or 13-bit count, then 11 bits per pair and 6 for an odd character.
+        const DATA_CODEWORDS: [[u16; 4]; 40] = [
+            [19, 16, 13, 9],
+            [34, 28, 22, 16],
+            [55, 44, 34, 26],
+            [80, 64, 48, 36],
+            [108, 86, 62, 46],
+            [136, 108, 76, 60],
+            [156, 124, 88, 66],
+            [194, 154, 110, 86],
+            [232, 182, 132, 100],
+            [274, 216, 154, 122],
+            [324, 254, 180, 140],
+            [370, 290, 206, 158],
+            [428, 334, 244, 180],
+            [461, 365, 261, 197],
+            [523, 415, 295, 223],
+            [589, 453, 325, 253],
+            [647, 507, 367, 283],
+            [721, 563, 397, 313],
+            [795, 627, 445, 341],
+            [861, 669, 485, 385],
+            [932, 714, 512, 406],
+            [1006, 782, 568, 442],
+            [1094, 860, 614, 464],
+            [1174, 914, 664, 514],
+            [1276, 1000, 718, 538],
+            [1370, 1062, 754, 596],
+            [1468, 1128, 808, 628],
+            [1531, 1193, 871, 661],
+            [1631, 1267, 911, 701],
+            [1735, 1373, 985, 745],
+            [1843, 1455, 1033, 793],
+            [1955, 1541, 1115, 845],
+            [2071, 1631, 1171, 901],
+            [2191, 1725, 1231, 961],
+            [2306, 1812, 1286, 986],
+            [2434, 1914, 1354, 1054],
+            [2566, 1992, 1426, 1096],
+            [2702, 2102, 1502, 1142],
+            [2812, 2216, 1582, 1222],
+            [2956, 2334, 1666, 1276],
+        ];
+        for (v, (caps, codewords)) in ALPHANUMERIC_CAPACITY
+            .iter()
+            .zip(&DATA_CODEWORDS)
+            .enumerate()
+        {
+            let count_bits = match v + 1 {
+                1..=9 => 9,
+                10..=26 => 11,
+                _ => 13,
+            };
+            for (&cap, &codewords) in caps.iter().zip(codewords) {
+                let bits = codewords as usize * 8 - 4 - count_bits;
+                assert_eq!(cap as usize, bits / 11 * 2 + usize::from(bits % 11 >= 6));
+            }
+        }
+    }
+
+    #[test]
+    fn version_is_the_smallest_that_fits() {
+        for ecc in [EccLevel::L, EccLevel::M, EccLevel::Q, EccLevel::H] {
+            assert_eq!(qr_version_for(0, ecc), Some(1));
+            for len in 0..3000 {
+                let chars = encoded_len(len);
+                let fits = |v| alphanumeric_capacity(v, ecc).unwrap() >= chars;
+                match qr_version_for(len, ecc) {
+                    Some(v) => assert!(fits(v) && (v == MIN_VERSION || !fits(v - 1))),
+                    None => assert!(!fits(MAX_VERSION)),
+                }
+            }
+        }
+        // 2864 bytes make 4296 characters, exactly version 40-L
+        assert_eq!(qr_version_for(2864, EccLevel::L), Some(40));
+        assert_eq!(qr_version_for(2865, EccLevel::L), None);
+        assert_eq!(qr_version_for(usize::MAX, EccLevel::L), None);
+        assert_eq!(alphanumeric_capacity(0, EccLevel::L), None);
+        assert_eq!(alphanumeric_capacity(41, EccLevel::L), None);
+        assert_eq!(byte_capacity(40, EccLevel::L), Some(2864));
+        assert_eq!(byte_capacity(1, EccLevel::H), Some(6));
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn chunks_fit_and_concatenate() {
+        let data: Vec<u8> = (0..5000u32).map(|i| (i * 167 + 13) as u8).collect();
+        for ecc in [EccLevel::L, EccLevel::M, EccLevel::Q, EccLevel::H] {
+            for version in [1, 2, 10, 27, 40] {
+                let capacity = alphanumeric_capacity(version, ecc).unwrap();
+                for len in [1, 2, 99, 5000] {
+                    let chunks = chunk_for_qr(&data[..len], version, ecc).unwrap();
+                    assert!(chunks.iter().all(|c| c.len() <= capacity));
+                    assert_eq!(chunks.concat(), crate::encode(&data[..len]));
+                    let whole = chunks[0].len();
+                    assert!(chunks[..chunks.len() - 1].iter().all(|c| c.len() == whole));
+                }
+            }
+        }
+        assert_eq!(chunk_for_qr(&[], 1, EccLevel::H).unwrap(), [""]);
+        let err = chunk_for_qr(b"ietf!", 41, EccLevel::L).unwrap_err();
+        assert_eq!(err.kind, ErrorKind::InvalidLength);
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn parts_fit_and_reassemble_in_any_order() {
+        let data: Vec<u8> = (0..5000u32).map(|i| (i * 167 + 13) as u8).collect();
+        for (version, ecc) in [
+            (1, EccLevel::Q),
+            (1, EccLevel::L),
+            (10, EccLevel::M),
+            (40, EccLevel::L),
+        ] {
+            let capacity = alphanumeric_capacity(version, ecc).unwrap();
+            for len in [0, 1, 2, 99, 2000] {
+                let parts = split_for_qr(&data[..len], version, ecc).unwrap();
+                assert!(parts.iter().all(|p| p.len() <= capacity), "{parts:?}");
+
+                let mut rx = Reassembler::new();
+                for (i, part) in parts.iter().enumerate().rev() {
+                    assert!(!rx.is_complete());
+                    assert_eq!(rx.push(part), Ok(true));
+                    assert_eq!(rx.push(part), Ok(false));
+                    assert_eq!(rx.missing().collect::<Vec<_>>(), (0..i).collect::<Vec<_>>());
+                }
+                assert_eq!(rx.total(), Some(parts.len()));
+                assert_eq!(rx.finish().unwrap(), &data[..len]);
+            }
+        }
+        // 99 bytes at 1-H: 10 characters hold a 6-character header and 2 bytes
+        assert_eq!(split_for_qr(&data[..99], 1, EccLevel::H).unwrap().len(), 50);
+        let err = split_for_qr(&data[..1], 0, EccLevel::L).unwrap_err();
+        assert_eq!(err.kind, ErrorKind::InvalidLength);
+        let err = split_for_qr(&[0; 2000], 1, EccLevel::H).unwrap_err();
+        assert_eq!(err.kind, ErrorKind::TooLarge);
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn reassembler_rejects_bad_parts() {
+        let mut rx = Reassembler::new();
+        assert_eq!(rx.total(), None);
+        assert_eq!(rx.missing().count(), 0);
+        for bad in [
+            "", "QED8WEX0", "0/0:", "2/2:", "+0/2:", "0/2", "0/2/3:", "00000/2:",
+        ] {
+            assert_eq!(
+                rx.push(bad).unwrap_err().kind,
+                ErrorKind::InvalidFrame,
+                "{bad}"
+            );
+        }
+        let err = rx.push("0/2:QED8WEx0").unwrap_err();
+        assert_eq!((err.kind, err.position), (ErrorKind::InvalidChar, 10));
+        assert_eq!(rx.total(), None);
+
+        assert_eq!(rx.push("1/2:X0"), Ok(true));
+        assert_eq!(
+            rx.push("0/3:QED8WE").unwrap_err().kind,
+            ErrorKind::InvalidFrame
+        );
+        assert_eq!(rx.push("1/2:X1").unwrap_err().kind, ErrorKind::InvalidFrame);
+        assert_eq!(rx.missing().collect::<Vec<_>>(), [0]);
+        assert_eq!(
+            rx.clone().finish().unwrap_err().kind,
+            ErrorKind::InvalidFrame
+        );
+        assert_eq!(rx.push("0/2:QED8WE"), Ok(true));
+        assert_eq!(rx.finish().unwrap(), b"ietf!");
+    }
+}
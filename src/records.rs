@@ -0,0 +1,51 @@
+//! Newline-delimited records: one Base45 payload per line, for logs and batch exports.
+
+use alloc::string::String;
+use alloc::vec::Vec;
+
+use crate::{Base45Error, decode, encode_into};
+
+/// Encode each record on its own line, each terminated by `\n`.
+/// Base45 never contains line breaks, so records need no further escaping.
+pub fn encode_records<T: AsRef<[u8]>>(records: &[T]) -> String {
+    let mut out = String::new();
+    for record in records {
+        encode_into(record, &mut out);
+        out.push('\n');
+    }
+    out
+}
+
+/// Decode each line of `s` as an independent record. `\n` and `\r\n` endings are accepted, and
+/// a bad line yields an error in its slot without affecting the others.
+pub fn decode_records(s: &str) -> Vec<Result<Vec<u8>, Base45Error>> {
+    s.lines().map(decode).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ErrorKind;
+
+    #[test]
+    fn records_roundtrip() {
+        let records: [&[u8]; 4] = [b"AB", b"", b"ietf!", b"Hello!!"];
+        let text = encode_records(&records);
+        assert_eq!(text, "BB8\n\nQED8WEX0\n%69 VD92EX0\n");
+        let decoded: Vec<Vec<u8>> = decode_records(&text)
+            .into_iter()
+            .map(Result::unwrap)
+            .collect();
+        assert_eq!(decoded, records);
+        assert!(decode_records(&encode_records::<&[u8]>(&[])).is_empty());
+    }
+
+    #[test]
+    fn bad_lines_are_isolated() {
+        let results = decode_records("BB8\r\nZZ\nQED8WEX0");
+        assert_eq!(results.len(), 3);
+        assert_eq!(results[0].as_deref().unwrap(), b"AB");
+        assert!(matches!(results[1], Err(e) if e.kind == ErrorKind::TailOverflow));
+        assert_eq!(results[2].as_deref().unwrap(), b"ietf!");
+    }
+}
@@ -0,0 +1,90 @@
+//! `schemars` support: JSON Schemas describing Base45 fields.
+
+use alloc::borrow::Cow;
+
+use schemars::{JsonSchema, Schema, SchemaGenerator, json_schema};
+
+use crate::{Base45Bytes, Base45String};
+
+/// Whole groups of 3 alphabet characters, then at most one group of 2. Group values above the
+/// RFC 9285 limits cannot be excluded by a pattern.
+const PATTERN: &str = "^(?:[0-9A-Z $%*+./:-]{3})*(?:[0-9A-Z $%*+./:-]{2})?$";
+
+impl JsonSchema for Base45String {
+    fn schema_name() -> Cow<'static, str> {
+        "Base45String".into()
+    }
+
+    fn schema_id() -> Cow<'static, str> {
+        "qr_base45::Base45String".into()
+    }
+
+    fn json_schema(_: &mut SchemaGenerator) -> Schema {
+        text_schema()
+    }
+}
+
+/// Described by its text form, which is how it is written and parsed.
+impl JsonSchema for Base45Bytes {
+    fn schema_name() -> Cow<'static, str> {
+        "Base45Bytes".into()
+    }
+
+    fn schema_id() -> Cow<'static, str> {
+        "qr_base45::Base45Bytes".into()
+    }
+
+    fn json_schema(_: &mut SchemaGenerator) -> Schema {
+        text_schema()
+    }
+}
+
+fn text_schema() -> Schema {
+    json_schema!({
+        "type": "string",
+        "pattern": PATTERN,
+        "description": "Base45 text (RFC 9285) over the QR alphanumeric alphabet.",
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::vec::Vec;
+
+    #[test]
+    fn schema_constrains_the_alphabet() {
+        let schema = schemars::schema_for!(Base45String);
+        assert_eq!(schema.get("type").unwrap(), "string");
+        assert_eq!(schema.get("pattern").unwrap(), PATTERN);
+        let schema = schemars::schema_for!(Base45Bytes);
+        assert_eq!(schema.get("pattern").unwrap(), PATTERN);
+
+        // The character class is exactly the alphabet: two ranges, then literals
+        let class = PATTERN.split(['[', ']']).nth(1).unwrap().as_bytes();
+        let mut chars: Vec<u8> = (b'0'..=b'9').chain(b'A'..=b'Z').collect();
+        assert_eq!(&class[..6], b"0-9A-Z");
+        chars.extend_from_slice(&class[6..]);
+        chars.sort_unstable();
+        let mut alphabet = crate::BASE45_ALPHABET.to_vec();
+        alphabet.sort_unstable();
+        assert_eq!(chars, alphabet);
+    }
+
+    #[test]
+    fn fields_reference_the_schema() {
+        #[derive(JsonSchema)]
+        #[allow(dead_code)]
+        struct Ticket {
+            payload: Base45String,
+        }
+
+        let schema = schemars::schema_for!(Ticket);
+        let field = &schema.get("properties").unwrap()["payload"];
+        assert_eq!(field["$ref"], "#/$defs/Base45String");
+        assert_eq!(
+            schema.get("$defs").unwrap()["Base45String"]["pattern"],
+            PATTERN
+        );
+    }
+}
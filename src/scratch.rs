@@ -0,0 +1,80 @@
+//! Reusable buffers for encoding and decoding many messages without allocating each time.
+
+use alloc::string::String;
+use alloc::vec::Vec;
+
+use crate::{Base45Error, decode_append, encode_into};
+
+/// Encode and decode buffers kept between calls. Once they have grown to the largest message
+/// seen, further calls do not allocate; keep one per worker or thread.
+///
+/// ```
+/// let mut scratch = qr_base45::Base45Scratch::new();
+/// assert_eq!(scratch.encode(b"ietf!"), "QED8WEX0");
+/// assert_eq!(scratch.decode("QED8WEX0").unwrap(), b"ietf!");
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct Base45Scratch {
+    encoded: String,
+    decoded: Vec<u8>,
+}
+
+impl Base45Scratch {
+    /// Empty buffers; the first calls allocate.
+    pub const fn new() -> Self {
+        Base45Scratch {
+            encoded: String::new(),
+            decoded: Vec::new(),
+        }
+    }
+
+    /// Buffers sized for inputs of up to `bytes` bytes and their encodings.
+    pub fn with_capacity(bytes: usize) -> Self {
+        Base45Scratch {
+            encoded: String::with_capacity(crate::encoded_len(bytes)),
+            decoded: Vec::with_capacity(bytes),
+        }
+    }
+
+    /// Encode `input` into the encode buffer and borrow the result until the next call.
+    pub fn encode<T: AsRef<[u8]>>(&mut self, input: T) -> &str {
+        self.encoded.clear();
+        encode_into(input, &mut self.encoded);
+        &self.encoded
+    }
+
+    /// Decode `s` into the decode buffer and borrow the bytes until the next call.
+    pub fn decode(&mut self, s: &str) -> Result<&[u8], Base45Error> {
+        self.decoded.clear();
+        decode_append(s, &mut self.decoded)?;
+        Ok(&self.decoded)
+    }
+
+    /// Release memory beyond `bytes` of input capacity, after an unusually large message.
+    pub fn shrink_to(&mut self, bytes: usize) {
+        self.encoded.shrink_to(crate::encoded_len(bytes));
+        self.decoded.shrink_to(bytes);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reuses_buffers() {
+        let mut scratch = Base45Scratch::with_capacity(64);
+        let (encoded, decoded) = (scratch.encoded.as_ptr(), scratch.decoded.as_ptr());
+        for case in [&b"ietf!"[..], b"", b"AB", &[0xFF; 64]] {
+            let text = scratch.encode(case).to_owned();
+            assert_eq!(text, crate::encode(case));
+            assert_eq!(scratch.decode(&text).unwrap(), case);
+        }
+        assert_eq!(scratch.encoded.as_ptr(), encoded);
+        assert_eq!(scratch.decoded.as_ptr(), decoded);
+
+        assert!(scratch.decode("BB8A").is_err());
+        scratch.shrink_to(0);
+        assert_eq!(scratch.encode(b"AB"), "BB8");
+    }
+}
@@ -0,0 +1,259 @@
+//! Serde field helpers: `#[serde(with = "qr_base45::serde")]` stores a byte field (`Vec<u8>`,
+//! `[u8; N]`, or anything else byte-like) as a Base45 string.
+//!
+//! ```
+//! use serde::{Deserialize, Serialize};
+//!
+//! #[derive(Serialize, Deserialize, PartialEq, Debug)]
+//! struct Config {
+//!     #[serde(with = "qr_base45::serde")]
+//!     key: [u8; 5],
+//!     #[serde(with = "qr_base45::serde")]
+//!     blob: Vec<u8>,
+//! }
+//!
+//! let config = Config { key: *b"ietf!", blob: b"AB".to_vec() };
+//! let json = serde_json::to_string(&config).unwrap();
+//! assert_eq!(json, r#"{"key":"QED8WEX0","blob":"BB8"}"#);
+//! assert_eq!(serde_json::from_str::<Config>(&json).unwrap(), config);
+//! ```
+//!
+//! [`Base45Bytes`] serializes as its Base45 text. [`Base45String`] implements `Serialize` and
+//! `Deserialize` too: as its text in human-readable formats, and as the decoded bytes in binary
+//! ones (bincode, CBOR), so the wire form is always the compact one.
+//!
+//! With the `serde_with` feature, [`Base45`] does the same inside `serde_as` annotations, where it
+//! composes with `Option`, `Vec` and the other containers `serde_with` knows.
+
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::fmt;
+use core::marker::PhantomData;
+
+use ::serde::de::{self, Deserialize, Deserializer, Visitor};
+use ::serde::ser::{Serialize, Serializer};
+
+use crate::{Base45Bytes, Base45String};
+
+/// Serialize `bytes` as their Base45 encoding.
+pub fn serialize<T, S>(bytes: &T, serializer: S) -> Result<S::Ok, S::Error>
+where
+    T: AsRef<[u8]> + ?Sized,
+    S: Serializer,
+{
+    serializer.serialize_str(&crate::encode(bytes.as_ref()))
+}
+
+/// Deserialize a Base45 string into any byte container buildable from a `Vec<u8>`. Invalid text
+/// fails with the decode error's message; for a fixed-size array, so does a length mismatch.
+pub fn deserialize<'de, T, D>(deserializer: D) -> Result<T, D::Error>
+where
+    T: TryFrom<Vec<u8>>,
+    D: Deserializer<'de>,
+{
+    deserializer.deserialize_str(Base45Visitor(PhantomData))
+}
+
+/// A `serde_with` adapter: `#[serde_as(as = "Base45")]` stores a byte field as a Base45 string,
+/// like [`serialize`] and [`deserialize`].
+///
+/// ```
+/// use qr_base45::serde::Base45;
+/// use serde::{Deserialize, Serialize};
+/// use serde_with::serde_as;
+///
+/// #[serde_as]
+/// #[derive(Serialize, Deserialize, PartialEq, Debug)]
+/// struct Batch {
+///     #[serde_as(as = "Option<Base45>")]
+///     key: Option<[u8; 5]>,
+///     #[serde_as(as = "Vec<Base45>")]
+///     items: Vec<Vec<u8>>,
+/// }
+///
+/// let batch = Batch { key: Some(*b"ietf!"), items: vec![b"AB".to_vec(), vec![]] };
+/// let json = serde_json::to_string(&batch).unwrap();
+/// assert_eq!(json, r#"{"key":"QED8WEX0","items":["BB8",""]}"#);
+/// assert_eq!(serde_json::from_str::<Batch>(&json).unwrap(), batch);
+/// ```
+#[cfg(feature = "serde_with")]
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Base45;
+
+#[cfg(feature = "serde_with")]
+impl<T: AsRef<[u8]> + ?Sized> serde_with::SerializeAs<T> for Base45 {
+    fn serialize_as<S: Serializer>(source: &T, serializer: S) -> Result<S::Ok, S::Error> {
+        serialize(source, serializer)
+    }
+}
+
+#[cfg(feature = "serde_with")]
+impl<'de, T: TryFrom<Vec<u8>>> serde_with::DeserializeAs<'de, T> for Base45 {
+    fn deserialize_as<D: Deserializer<'de>>(deserializer: D) -> Result<T, D::Error> {
+        deserialize(deserializer)
+    }
+}
+
+struct Base45Visitor<T>(PhantomData<T>);
+
+impl<T: TryFrom<Vec<u8>>> Visitor<'_> for Base45Visitor<T> {
+    type Value = T;
+
+    fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("a Base45 string")
+    }
+
+    fn visit_str<E: de::Error>(self, v: &str) -> Result<T, E> {
+        let bytes = crate::decode(v).map_err(E::custom)?;
+        let len = bytes.len();
+        T::try_from(bytes).map_err(|_| E::invalid_length(len, &"the field's length in bytes"))
+    }
+}
+
+impl Serialize for Base45String {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        if serializer.is_human_readable() {
+            serializer.serialize_str(self)
+        } else {
+            serializer.serialize_bytes(&self.decode())
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for Base45String {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        if deserializer.is_human_readable() {
+            deserializer.deserialize_str(Base45StringVisitor)
+        } else {
+            deserializer.deserialize_bytes(Base45StringVisitor)
+        }
+    }
+}
+
+/// As its Base45 text, in every format.
+impl Serialize for Base45Bytes {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serialize(&self.0, serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for Base45Bytes {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserialize(deserializer).map(Base45Bytes)
+    }
+}
+
+/// Validates text, or encodes the bytes of a binary format.
+struct Base45StringVisitor;
+
+impl Visitor<'_> for Base45StringVisitor {
+    type Value = Base45String;
+
+    fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("a Base45 string or its decoded bytes")
+    }
+
+    fn visit_str<E: de::Error>(self, v: &str) -> Result<Base45String, E> {
+        v.parse().map_err(E::custom)
+    }
+
+    fn visit_string<E: de::Error>(self, v: String) -> Result<Base45String, E> {
+        Base45String::try_from(v).map_err(E::custom)
+    }
+
+    fn visit_bytes<E: de::Error>(self, v: &[u8]) -> Result<Base45String, E> {
+        Ok(Base45String::encode(v))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ::serde::{Deserialize, Serialize};
+
+    #[derive(Serialize, Deserialize, PartialEq, Debug)]
+    struct Record {
+        #[serde(with = "crate::serde")]
+        id: [u8; 4],
+        #[serde(with = "crate::serde")]
+        payload: Vec<u8>,
+    }
+
+    #[test]
+    fn fields_round_trip() {
+        let record = Record {
+            id: [0, 1, 0xFE, 0xFF],
+            payload: Vec::new(),
+        };
+        let json = serde_json::to_string(&record).unwrap();
+        assert_eq!(
+            json,
+            format!(r#"{{"id":"{}","payload":""}}"#, crate::encode(record.id))
+        );
+        assert_eq!(serde_json::from_str::<Record>(&json).unwrap(), record);
+    }
+
+    #[test]
+    fn bad_fields_fail() {
+        let err = serde_json::from_str::<Record>(r#"{"id":"BB8a","payload":""}"#).unwrap_err();
+        assert!(err.to_string().contains("index 3"), "{err}");
+        let err = serde_json::from_str::<Record>(r#"{"id":"BB8","payload":""}"#).unwrap_err();
+        assert!(err.to_string().contains("invalid length 2"), "{err}");
+        assert!(serde_json::from_str::<Record>(r#"{"id":7,"payload":""}"#).is_err());
+    }
+
+    #[cfg(feature = "serde_with")]
+    #[test]
+    fn serde_as_composes_with_containers() {
+        use super::Base45;
+        use serde_with::serde_as;
+
+        #[serde_as]
+        #[derive(Serialize, Deserialize, PartialEq, Debug)]
+        struct Nested {
+            #[serde_as(as = "Option<Base45>")]
+            maybe: Option<Vec<u8>>,
+            #[serde_as(as = "Vec<Vec<Base45>>")]
+            grid: Vec<Vec<[u8; 2]>>,
+        }
+
+        let nested = Nested {
+            maybe: None,
+            grid: vec![vec![*b"AB", *b"ie"], vec![]],
+        };
+        let json = serde_json::to_string(&nested).unwrap();
+        assert_eq!(json, r#"{"maybe":null,"grid":[["BB8","QED"],[]]}"#);
+        assert_eq!(serde_json::from_str::<Nested>(&json).unwrap(), nested);
+        let bad = r#"{"maybe":"BB8","grid":[["QED8WEX0"]]}"#;
+        assert!(serde_json::from_str::<Nested>(bad).is_err());
+    }
+
+    #[test]
+    fn base45_string_is_text_or_bytes_by_format() {
+        let s = crate::Base45String::encode(b"ietf!");
+        let json = serde_json::to_string(&s).unwrap();
+        assert_eq!(json, r#""QED8WEX0""#);
+        assert_eq!(
+            serde_json::from_str::<crate::Base45String>(&json).unwrap(),
+            s
+        );
+        assert!(serde_json::from_str::<crate::Base45String>(r#""BB8A""#).is_err());
+
+        // CBOR carries the 5 decoded bytes, not the 8 characters
+        let mut cbor = Vec::new();
+        ciborium::into_writer(&s, &mut cbor).unwrap();
+        assert_eq!(cbor, b"\x45ietf!");
+        let back: crate::Base45String = ciborium::from_reader(&cbor[..]).unwrap();
+        assert_eq!(back, s);
+    }
+
+    #[test]
+    fn base45_bytes_is_text() {
+        let bytes = crate::Base45Bytes(b"ietf!".to_vec());
+        let json = serde_json::to_string(&bytes).unwrap();
+        assert_eq!(json, r#""QED8WEX0""#);
+        assert_eq!(
+            serde_json::from_str::<crate::Base45Bytes>(&json).unwrap(),
+            bytes
+        );
+    }
+}
@@ -0,0 +1,280 @@
+//! Vectorized encoding and decoding behind the `simd` feature. With `std`, x86_64 and aarch64
+//! pick the best path for the running CPU on first use and cache it as a function pointer; under
+//! `no_std` and `no-panic` the choice is made at compile time from the enabled target features.
+//! wasm32 uses SIMD128 wherever the target enables it. The nightly `portable-simd` feature adds
+//! a `core::simd` implementation for every target, used where no intrinsic path applies. Callers
+//! finish whatever is left with the scalar code; without either feature nothing is done here.
+
+#[cfg(all(feature = "simd", target_arch = "aarch64"))]
+mod neon;
+#[cfg(feature = "portable-simd")]
+mod portable;
+#[cfg(all(feature = "simd", target_arch = "wasm32", target_feature = "simd128"))]
+mod wasm;
+#[cfg(all(feature = "simd", target_arch = "x86_64"))]
+mod x86;
+
+/// Whether the CPU supports the target feature `$feature`.
+#[cfg(all(feature = "simd", any(target_arch = "x86_64", target_arch = "aarch64")))]
+macro_rules! cpu_has {
+    ($feature:tt) => {{
+        // Runtime detection calls into `std` code the `no-panic` check cannot see through
+        #[cfg(all(feature = "std", not(feature = "no-panic"), target_arch = "x86_64"))]
+        let has = std::is_x86_feature_detected!($feature);
+        #[cfg(all(feature = "std", not(feature = "no-panic"), target_arch = "aarch64"))]
+        let has = std::arch::is_aarch64_feature_detected!($feature);
+        #[cfg(any(not(feature = "std"), feature = "no-panic"))]
+        let has = cfg!(target_feature = $feature);
+        has
+    }};
+}
+
+/// Signature shared by every `encode_blocks` and `decode_blocks` implementation.
+type BlocksFn = unsafe fn(&[u8], &mut [u8]) -> usize;
+
+/// The path used when the CPU has no vector support: consumes nothing.
+unsafe fn no_blocks(input: &[u8], out: &mut [u8]) -> usize {
+    let _ = (input, out);
+    0
+}
+
+/// The best encoder the CPU supports.
+#[inline(always)]
+fn select_encode() -> BlocksFn {
+    #[cfg(all(feature = "simd", target_arch = "x86_64"))]
+    if cpu_has!("ssse3") {
+        return x86::encode_blocks;
+    }
+    #[cfg(all(feature = "simd", target_arch = "aarch64"))]
+    if cpu_has!("neon") {
+        return neon::encode_blocks;
+    }
+    #[cfg(all(feature = "simd", target_arch = "wasm32", target_feature = "simd128"))]
+    return wasm::encode_blocks;
+    #[cfg(feature = "portable-simd")]
+    #[allow(unreachable_code)]
+    return portable::encode_blocks;
+    #[allow(unreachable_code)]
+    no_blocks
+}
+
+/// The best decoder the CPU supports.
+#[inline(always)]
+fn select_decode() -> BlocksFn {
+    #[cfg(all(feature = "simd", target_arch = "x86_64"))]
+    if cpu_has!("avx2") {
+        return x86::decode_blocks;
+    }
+    #[cfg(all(feature = "simd", target_arch = "aarch64"))]
+    if cpu_has!("neon") {
+        return neon::decode_blocks;
+    }
+    #[cfg(all(feature = "simd", target_arch = "wasm32", target_feature = "simd128"))]
+    return wasm::decode_blocks;
+    #[cfg(feature = "portable-simd")]
+    #[allow(unreachable_code)]
+    return portable::decode_blocks;
+    #[allow(unreachable_code)]
+    no_blocks
+}
+
+/// Function pointers chosen by runtime detection. Each slot starts at a stub that selects the
+/// implementation, stores it for later calls and forwards the first one.
+#[cfg(all(
+    feature = "simd",
+    feature = "std",
+    not(feature = "no-panic"),
+    any(target_arch = "x86_64", target_arch = "aarch64")
+))]
+mod cached {
+    use core::sync::atomic::{AtomicPtr, Ordering};
+
+    use super::BlocksFn;
+
+    pub(super) static ENCODE: AtomicPtr<()> = AtomicPtr::new(detect_encode as *mut ());
+    pub(super) static DECODE: AtomicPtr<()> = AtomicPtr::new(detect_decode as *mut ());
+
+    /// The function currently stored in `slot`.
+    #[inline]
+    pub(super) fn load(slot: &AtomicPtr<()>) -> BlocksFn {
+        // SAFETY: slots only ever hold `BlocksFn` pointers. Racing detections store the same one.
+        unsafe { core::mem::transmute::<*mut (), BlocksFn>(slot.load(Ordering::Relaxed)) }
+    }
+
+    pub(super) unsafe fn detect_encode(input: &[u8], out: &mut [u8]) -> usize {
+        let f = super::select_encode();
+        ENCODE.store(f as *mut (), Ordering::Relaxed);
+        // SAFETY: `f` suits this CPU; the caller upholds the `encode_blocks` contract.
+        unsafe { f(input, out) }
+    }
+
+    pub(super) unsafe fn detect_decode(input: &[u8], out: &mut [u8]) -> usize {
+        let f = super::select_decode();
+        DECODE.store(f as *mut (), Ordering::Relaxed);
+        // SAFETY: `f` suits this CPU; the caller upholds the `decode_blocks` contract.
+        unsafe { f(input, out) }
+    }
+}
+
+/// Encode as many whole blocks of `input` as the CPU supports into `out`, which must be exactly
+/// `encoded_len(input.len())` bytes long. Returns the number of input bytes consumed, a multiple
+/// of the block size; 0 when no vector path is available.
+#[inline]
+pub(crate) fn encode_blocks(input: &[u8], out: &mut [u8]) -> usize {
+    #[cfg(all(
+        feature = "simd",
+        feature = "std",
+        not(feature = "no-panic"),
+        any(target_arch = "x86_64", target_arch = "aarch64")
+    ))]
+    let f = cached::load(&cached::ENCODE);
+    #[cfg(not(all(
+        feature = "simd",
+        feature = "std",
+        not(feature = "no-panic"),
+        any(target_arch = "x86_64", target_arch = "aarch64")
+    )))]
+    let f = select_encode();
+    // SAFETY: `f` was selected for this CPU.
+    unsafe { f(input, out) }
+}
+
+/// Output bytes [`decode_blocks`] may write for `n` input characters: 0 without a vector path.
+#[cfg(feature = "alloc")]
+#[inline]
+pub(crate) const fn decoded_block_len(n: usize) -> usize {
+    #[cfg(all(feature = "simd", target_arch = "aarch64"))]
+    return n / neon::DECODE_BLOCK * (neon::DECODE_BLOCK / 3 * 2);
+    #[cfg(all(feature = "simd", target_arch = "x86_64"))]
+    return n / x86::DECODE_BLOCK * (x86::DECODE_BLOCK / 3 * 2);
+    #[cfg(all(feature = "simd", target_arch = "wasm32", target_feature = "simd128"))]
+    return n / wasm::DECODE_BLOCK * (wasm::DECODE_BLOCK / 3 * 2);
+    #[cfg(feature = "portable-simd")]
+    #[allow(unreachable_code)]
+    return n / portable::DECODE_BLOCK * (portable::DECODE_BLOCK / 3 * 2);
+    #[allow(unreachable_code)]
+    {
+        let _ = n;
+        0
+    }
+}
+
+/// Decode as many whole blocks of the Base45 text `input` as the CPU supports into `out`, which
+/// must hold [`decoded_block_len`] bytes. Returns the number of input characters consumed, a
+/// multiple of the block size; it stops before the first block that fails to decode, leaving
+/// the error to the scalar code.
+#[inline]
+pub(crate) fn decode_blocks(input: &[u8], out: &mut [u8]) -> usize {
+    #[cfg(all(
+        feature = "simd",
+        feature = "std",
+        not(feature = "no-panic"),
+        any(target_arch = "x86_64", target_arch = "aarch64")
+    ))]
+    let f = cached::load(&cached::DECODE);
+    #[cfg(not(all(
+        feature = "simd",
+        feature = "std",
+        not(feature = "no-panic"),
+        any(target_arch = "x86_64", target_arch = "aarch64")
+    )))]
+    let f = select_decode();
+    // SAFETY: `f` was selected for this CPU.
+    unsafe { f(input, out) }
+}
+
+#[cfg(all(test, feature = "alloc"))]
+mod tests {
+    use alloc::string::String;
+    use alloc::vec::Vec;
+
+    #[test]
+    fn encode_matches_scalar_at_every_length() {
+        let data: Vec<u8> = (0..200u32).map(|i| (i * 167 + 13) as u8).collect();
+        for len in 0..=data.len() {
+            let scalar: String = crate::encode_iter(&data[..len]).collect();
+            assert_eq!(crate::encode(&data[..len]), scalar);
+        }
+    }
+
+    #[test]
+    fn decode_matches_scalar_at_every_length() {
+        let data: Vec<u8> = (0..200u32).map(|i| (i * 167 + 13) as u8).collect();
+        for len in 0..=data.len() {
+            let s = crate::encode(&data[..len]);
+            assert_eq!(crate::decode(&s).unwrap(), &data[..len]);
+            let mut out = [0u8; 200];
+            assert_eq!(crate::decode_slice(&s, &mut out), Ok(len));
+            assert_eq!(out[..len], data[..len]);
+        }
+    }
+
+    #[test]
+    fn decode_errors_match_scalar_inside_blocks() {
+        let text = crate::encode([0x5A; 100]);
+        for (at, bad) in [(0, b'a'), (17, b'~'), (47, 0xC3), (95, b'\n')] {
+            let mut bytes = text.clone().into_bytes();
+            bytes[at] = bad;
+            let err = crate::decode_bytes(&bytes).unwrap_err();
+            assert_eq!(
+                (err.kind, err.position),
+                (crate::ErrorKind::InvalidByte, at)
+            );
+        }
+        // Overflowing groups in either half of a block
+        for at in [0, 21, 24, 45] {
+            let mut s = text.clone();
+            s.replace_range(at..at + 3, ":::");
+            let err = crate::decode(&s).unwrap_err();
+            assert_eq!((err.kind, err.position), (crate::ErrorKind::Overflow, at));
+        }
+        // The largest group value is still accepted in every position
+        let s = crate::encode([0xFF; 64]);
+        assert_eq!(crate::decode(&s).unwrap(), [0xFF; 64]);
+    }
+
+    #[cfg(all(feature = "simd", feature = "portable-simd"))]
+    #[test]
+    fn intrinsics_match_portable() {
+        let data: Vec<u8> = (0..480u32).map(|i| (i * 167 + 13) as u8).collect();
+        let (mut simd, mut portable) = ([0u8; 720], [0u8; 720]);
+        // SAFETY: the selected path suits this CPU; both buffers hold the encoded length.
+        let n = unsafe { super::select_encode()(&data, &mut simd) };
+        assert_eq!(n, super::portable::encode_blocks(&data, &mut portable));
+        assert_eq!(simd[..n / 2 * 3], portable[..n / 2 * 3]);
+
+        let text = crate::encode(&data).into_bytes();
+        for (at, bad) in [
+            (None, 0),
+            (Some(100), b'a'),
+            (Some(200), b':'),
+            (Some(719), 0xFF),
+        ] {
+            let mut text = text.clone();
+            if let Some(at) = at {
+                text[at] = bad;
+            }
+            let (mut simd, mut portable) = ([0u8; 480], [0u8; 480]);
+            // SAFETY: as above; both buffers hold the decoded length.
+            let n = unsafe { super::select_decode()(&text, &mut simd) };
+            assert_eq!(n, super::portable::decode_blocks(&text, &mut portable));
+            assert_eq!(simd[..n / 3 * 2], portable[..n / 3 * 2]);
+        }
+    }
+
+    #[cfg(all(
+        feature = "simd",
+        feature = "std",
+        not(feature = "no-panic"),
+        any(target_arch = "x86_64", target_arch = "aarch64")
+    ))]
+    #[test]
+    fn detection_runs_once() {
+        use super::cached::{DECODE, ENCODE, detect_decode, detect_encode, load};
+
+        let text = crate::encode([7; 64]);
+        assert_eq!(crate::decode(&text).unwrap(), [7; 64]);
+        assert_ne!(load(&ENCODE) as *mut (), detect_encode as *mut ());
+        assert_ne!(load(&DECODE) as *mut (), detect_decode as *mut ());
+    }
+}
@@ -0,0 +1,197 @@
+//! NEON encoder: 16 input bytes (8 groups) to 24 characters per step.
+//! NEON decoder: 48 characters (16 groups) to 32 bytes per step.
+//!
+//! The interleaving loads and stores (`vld3`, `vst2`, `vst3`) split and join the digits of each
+//! group, and table lookups map between digits and characters.
+
+// Register-only intrinsics are safe inside `#[target_feature]` functions from Rust 1.87 on; the
+// `unsafe` blocks around them are for the 1.85 MSRV.
+#![allow(unused_unsafe)]
+
+use core::arch::aarch64::*;
+
+use crate::{ALPHABET_64, DECODE_TABLE, DIV45_MUL, DIV45_SHIFT};
+
+/// Input bytes consumed per encode block.
+pub(crate) const ENCODE_BLOCK: usize = 16;
+/// Input characters consumed per decode block.
+pub(crate) const DECODE_BLOCK: usize = 48;
+
+/// Digit values of the characters `0x20..0x60`, indexed by `char - 0x20`.
+const DIGITS_FROM_SPACE: [u8; 64] = {
+    let mut table = [0; 64];
+    let mut i = 0;
+    while i < 64 {
+        table[i] = DECODE_TABLE[0x20 + i];
+        i += 1;
+    }
+    table
+};
+
+/// See [`super::encode_blocks`].
+///
+/// # Safety
+///
+/// The CPU must support NEON.
+#[inline]
+#[target_feature(enable = "neon")]
+pub(crate) unsafe fn encode_blocks(input: &[u8], out: &mut [u8]) -> usize {
+    // SAFETY: ALPHABET_64 is 48 readable bytes and more.
+    let alphabet = unsafe { vld1q_u8_x3(ALPHABET_64.as_ptr()) };
+    let mut n = 0;
+    for (block, dst) in input
+        .chunks_exact(ENCODE_BLOCK)
+        .zip(out.chunks_exact_mut(ENCODE_BLOCK / 2 * 3))
+    {
+        // SAFETY: `block` holds 16 readable bytes and `dst` 24 writable ones.
+        unsafe { encode_block(block.as_ptr(), dst.as_mut_ptr(), alphabet) };
+        n += ENCODE_BLOCK;
+    }
+    n
+}
+
+/// Encode the 16 bytes at `src` into the 24 characters at `dst`.
+#[inline]
+#[target_feature(enable = "neon")]
+unsafe fn encode_block(src: *const u8, dst: *mut u8, alphabet: uint8x16x3_t) {
+    // SAFETY: the caller guarantees 16 readable bytes.
+    let bytes = unsafe { vld1q_u8(src) };
+    // SAFETY: NEON is enabled for this function and the caller's CPU supports it, and the caller
+    // guarantees 24 writable bytes.
+    unsafe {
+        // Big-endian byte pairs to 16-bit group values
+        let x = vreinterpretq_u16_u8(vrev16q_u8(bytes));
+        let q = div45(x);
+        let a = div45(q);
+        let c = vmlsq_n_u16(x, q, 45);
+        let b = vmlsq_n_u16(q, a, 45);
+        // Digits are below 45, inside the 48-byte table
+        let ch = |d: uint16x8_t| vqtbl3_u8(alphabet, vmovn_u16(d));
+        vst3_u8(dst, uint8x8x3_t(ch(c), ch(b), ch(a)));
+    }
+}
+
+/// `x / 45` in each lane, by reciprocal multiplication.
+///
+/// # Safety
+///
+/// The CPU must support NEON.
+#[inline]
+#[target_feature(enable = "neon")]
+unsafe fn div45(x: uint16x8_t) -> uint16x8_t {
+    // SAFETY: NEON is enabled for this function and the caller's CPU supports it.
+    unsafe {
+        let m = vdup_n_u16(DIV45_MUL as u16);
+        let lo = vshrn_n_u32::<16>(vmull_u16(vget_low_u16(x), m));
+        let hi = vshrn_n_u32::<16>(vmull_high_u16(x, vcombine_u16(m, m)));
+        vshrq_n_u16::<{ DIV45_SHIFT as i32 - 16 }>(vcombine_u16(lo, hi))
+    }
+}
+
+/// See [`super::decode_blocks`].
+///
+/// # Safety
+///
+/// The CPU must support NEON.
+#[inline]
+#[target_feature(enable = "neon")]
+pub(crate) unsafe fn decode_blocks(input: &[u8], out: &mut [u8]) -> usize {
+    // SAFETY: DIGITS_FROM_SPACE is 64 readable bytes.
+    let table = unsafe { vld1q_u8_x4(DIGITS_FROM_SPACE.as_ptr()) };
+    let mut n = 0;
+    for (block, dst) in input
+        .chunks_exact(DECODE_BLOCK)
+        .zip(out.chunks_exact_mut(DECODE_BLOCK / 3 * 2))
+    {
+        // SAFETY: `block` holds 48 readable bytes and `dst` 32 writable ones.
+        if !unsafe { decode_block(block.as_ptr(), dst.as_mut_ptr(), table) } {
+            break;
+        }
+        n += DECODE_BLOCK;
+    }
+    n
+}
+
+/// Decode the 48 characters at `src` into the 32 bytes at `dst`. Returns `false`, with `dst`
+/// unspecified, if any character is outside the alphabet or any group overflows.
+#[inline]
+#[target_feature(enable = "neon")]
+unsafe fn decode_block(src: *const u8, dst: *mut u8, table: uint8x16x4_t) -> bool {
+    // SAFETY: the caller guarantees 48 readable bytes.
+    let uint8x16x3_t(c, b, a) = unsafe { vld3q_u8(src) };
+    // Characters outside 0x20..0x60 fall outside the table and keep the 0xFF default
+    // SAFETY: NEON is enabled for this function and the caller's CPU supports it.
+    let digits = |v: uint8x16_t| unsafe {
+        vqtbx4q_u8(vdupq_n_u8(0xFF), table, vsubq_u8(v, vdupq_n_u8(0x20)))
+    };
+    let (c, b, a) = (digits(c), digits(b), digits(a));
+    // Digits are below 45 and invalid characters map to 0xFF, so the top bit flags them
+    // SAFETY: NEON is available as above.
+    if unsafe { vmaxvq_u8(vorrq_u8(vorrq_u8(c, b), a)) } & 0x80 != 0 {
+        return false;
+    }
+    // t = b * 45 + c, then a * 2025 + t, in 16-bit lanes for each half of the groups
+    // SAFETY: NEON is available as above.
+    let (t_lo, t_hi, a_lo, a_hi) = unsafe {
+        (
+            vmlal_u8(vmovl_u8(vget_low_u8(c)), vget_low_u8(b), vdup_n_u8(45)),
+            vmlal_high_u8(vmovl_high_u8(c), b, vdupq_n_u8(45)),
+            vmovl_u8(vget_low_u8(a)),
+            vmovl_high_u8(a),
+        )
+    };
+    // The value fits 16 bits unless a > 32, or a == 32 and t > 65535 - 32 * 2025
+    // SAFETY: NEON is available as above.
+    let overflow = |a: uint16x8_t, t: uint16x8_t| unsafe {
+        vorrq_u16(
+            vcgtq_u16(a, vdupq_n_u16(32)),
+            vandq_u16(
+                vceqq_u16(a, vdupq_n_u16(32)),
+                vcgtq_u16(t, vdupq_n_u16(u16::MAX - 32 * 2025)),
+            ),
+        )
+    };
+    // SAFETY: NEON is available as above.
+    if unsafe { vmaxvq_u16(vorrq_u16(overflow(a_lo, t_lo), overflow(a_hi, t_hi))) } != 0 {
+        return false;
+    }
+    // SAFETY: NEON is available as above, and the caller guarantees 32 writable bytes.
+    unsafe {
+        let x_lo = vmlaq_n_u16(t_lo, a_lo, 2025);
+        let x_hi = vmlaq_n_u16(t_hi, a_hi, 2025);
+        // High and low bytes of each value, stored interleaved as big-endian pairs
+        let high = vcombine_u8(vshrn_n_u16::<8>(x_lo), vshrn_n_u16::<8>(x_hi));
+        let low = vcombine_u8(vmovn_u16(x_lo), vmovn_u16(x_hi));
+        vst2q_u8(dst, uint8x16x2_t(high, low));
+    }
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::encode_pair;
+
+    #[test]
+    fn blocks_match_scalar() {
+        for start in (0..=u16::MAX).step_by(8) {
+            let mut src = [0u8; 16];
+            for (k, pair) in src.chunks_exact_mut(2).enumerate() {
+                pair.copy_from_slice(&start.wrapping_add(k as u16).to_be_bytes());
+            }
+            let mut chars = [0u8; 48];
+            let mut back = [0u8; 32];
+            // SAFETY: NEON is enabled for the target; the buffers are large enough.
+            unsafe {
+                encode_blocks(&src, &mut chars[..24]);
+                chars.copy_within(..24, 24);
+                assert_eq!(decode_blocks(&chars, &mut back), DECODE_BLOCK);
+            }
+            for (k, group) in chars[..24].chunks_exact(3).enumerate() {
+                assert_eq!(group, encode_pair(start.wrapping_add(k as u16)));
+            }
+            assert_eq!(back[..16], src);
+            assert_eq!(back[16..], src);
+        }
+    }
+}
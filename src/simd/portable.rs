@@ -0,0 +1,190 @@
+//! `core::simd` encoder and decoder, for nightly toolchains: 16 input bytes (8 groups) to 24
+//! characters and 48 characters (16 groups) to 32 bytes per step, on any target. Written for
+//! clarity over speed; the intrinsic paths must agree with it.
+
+use core::simd::prelude::*;
+use core::simd::simd_swizzle;
+
+use crate::{DECODE_TABLE, DIV45_MUL, DIV45_SHIFT};
+
+/// Input bytes consumed per encode block.
+pub(crate) const ENCODE_BLOCK: usize = 16;
+/// Input characters consumed per decode block.
+pub(crate) const DECODE_BLOCK: usize = 48;
+
+/// Characters for digits 36..=44, indexed by `digit - 36`.
+const SPECIALS: [u8; 16] = *b" $%*+-./:\0\0\0\0\0\0\0";
+
+/// Lanes of `[c0..c7, b0..b7]` and `[a0..a7]` giving `c0 b0 a0 c1 b1 a1 ...`, 8 lanes of padding
+/// after the 24 characters.
+const INTERLEAVE: [usize; 32] = {
+    let mut lanes = [0; 32];
+    let mut pos = 0;
+    while pos < 24 {
+        let (group, digit) = (pos / 3, pos % 3);
+        lanes[pos] = 8 * digit + group;
+        pos += 1;
+    }
+    lanes
+};
+
+/// Lanes of the 48 characters holding digit `k` (0 = least significant) of each group.
+const fn digit_lanes(k: usize) -> [usize; 16] {
+    let mut lanes = [0; 16];
+    let mut g = 0;
+    while g < 16 {
+        lanes[g] = 3 * g + k;
+        g += 1;
+    }
+    lanes
+}
+
+/// Digit values of the characters `0x20..0x60`, indexed by `char - 0x20`.
+const DIGITS_FROM_SPACE: [u8; 64] = {
+    let mut table = [0; 64];
+    let mut i = 0;
+    while i < 64 {
+        table[i] = DECODE_TABLE[0x20 + i];
+        i += 1;
+    }
+    table
+};
+
+/// See [`super::encode_blocks`].
+#[inline]
+pub(crate) fn encode_blocks(input: &[u8], out: &mut [u8]) -> usize {
+    let mut n = 0;
+    for (block, dst) in input
+        .chunks_exact(ENCODE_BLOCK)
+        .zip(out.chunks_exact_mut(ENCODE_BLOCK / 2 * 3))
+    {
+        encode_block(block, dst);
+        n += ENCODE_BLOCK;
+    }
+    n
+}
+
+/// Encode the 16 bytes of `src` into the 24 characters of `dst`.
+#[inline]
+fn encode_block(src: &[u8], dst: &mut [u8]) {
+    let bytes = u8x16::load_or_default(src);
+    // Big-endian byte pairs to 16-bit group values
+    let high: u8x8 = simd_swizzle!(bytes, [0, 2, 4, 6, 8, 10, 12, 14]);
+    let low: u8x8 = simd_swizzle!(bytes, [1, 3, 5, 7, 9, 11, 13, 15]);
+    let x = (high.cast::<u16>() << 8) | low.cast::<u16>();
+    let q = div45(x);
+    let a = div45(q);
+    let c = x - q * u16x8::splat(45);
+    let b = q - a * u16x8::splat(45);
+    let cb = to_ascii(simd_swizzle!(
+        c.cast::<u8>(),
+        b.cast::<u8>(),
+        [0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15]
+    ));
+    let a = to_ascii(simd_swizzle!(
+        a.cast::<u8>(),
+        u8x8::splat(0),
+        [0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15]
+    ));
+    let chars: u8x32 = simd_swizzle!(cb, a, INTERLEAVE);
+    dst.copy_from_slice(&chars.as_array()[..24]);
+}
+
+/// `x / 45` in each lane, by reciprocal multiplication.
+#[inline]
+fn div45(x: u16x8) -> u16x8 {
+    ((x.cast::<u32>() * u32x8::splat(DIV45_MUL)) >> DIV45_SHIFT).cast()
+}
+
+/// Map each digit (0..45) to its character in the alphabet.
+#[inline]
+fn to_ascii(d: u8x16) -> u8x16 {
+    let alnum = d
+        .simd_lt(u8x16::splat(10))
+        .select(d + u8x16::splat(b'0'), d + u8x16::splat(b'A' - 10));
+    // Lanes below 36 wrap past the table and select 0
+    let special = u8x16::from_array(SPECIALS).swizzle_dyn(d - u8x16::splat(36));
+    d.simd_lt(u8x16::splat(36)).select(alnum, special)
+}
+
+/// See [`super::decode_blocks`].
+#[inline]
+pub(crate) fn decode_blocks(input: &[u8], out: &mut [u8]) -> usize {
+    let mut n = 0;
+    for (block, dst) in input
+        .chunks_exact(DECODE_BLOCK)
+        .zip(out.chunks_exact_mut(DECODE_BLOCK / 3 * 2))
+    {
+        if !decode_block(block, dst) {
+            break;
+        }
+        n += DECODE_BLOCK;
+    }
+    n
+}
+
+/// Decode the 48 characters of `src` into the 32 bytes of `dst`. Returns `false`, with `dst`
+/// unspecified, if any character is outside the alphabet or any group overflows.
+#[inline]
+fn decode_block(src: &[u8], dst: &mut [u8]) -> bool {
+    let chars = u8x64::load_or_default(src);
+    let index = chars - u8x64::splat(0x20);
+    // Characters outside 0x20..0x60 index past the table and select 0
+    let digits = u8x64::from_array(DIGITS_FROM_SPACE).swizzle_dyn(index);
+    let invalid = index.simd_ge(u8x64::splat(64)) | digits.simd_eq(u8x64::splat(0xFF));
+    // Only the first 48 lanes hold characters
+    if invalid.to_bitmask() & ((1 << DECODE_BLOCK) - 1) != 0 {
+        return false;
+    }
+    let c: u8x16 = simd_swizzle!(digits, digit_lanes(0));
+    let b: u8x16 = simd_swizzle!(digits, digit_lanes(1));
+    let a: u8x16 = simd_swizzle!(digits, digit_lanes(2));
+    let (c, b, a) = (c.cast::<u16>(), b.cast::<u16>(), a.cast::<u16>());
+    let t = b * u16x16::splat(45) + c;
+    // The value a * 2025 + t fits 16 bits unless a > 32, or a == 32 and t > 65535 - 32 * 2025
+    let overflow = a.simd_gt(u16x16::splat(32))
+        | (a.simd_eq(u16x16::splat(32)) & t.simd_gt(u16x16::splat(u16::MAX - 32 * 2025)));
+    if overflow.any() {
+        return false;
+    }
+    let x = a * u16x16::splat(2025) + t;
+    // 16-bit values to big-endian byte pairs
+    let bytes: u8x32 = simd_swizzle!(
+        (x >> 8).cast::<u8>(),
+        x.cast::<u8>(),
+        [
+            0, 16, 1, 17, 2, 18, 3, 19, 4, 20, 5, 21, 6, 22, 7, 23, 8, 24, 9, 25, 10, 26, 11, 27,
+            12, 28, 13, 29, 14, 30, 15, 31
+        ]
+    );
+    bytes.copy_to_slice(dst);
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::encode_pair;
+
+    #[test]
+    fn encode_block_matches_scalar() {
+        for start in (0..=u16::MAX).step_by(8) {
+            let mut src = [0u8; 16];
+            for (k, pair) in src.chunks_exact_mut(2).enumerate() {
+                pair.copy_from_slice(&start.wrapping_add(k as u16).to_be_bytes());
+            }
+            let mut dst = [0u8; 24];
+            encode_block(&src, &mut dst);
+            for (k, chars) in dst.chunks_exact(3).enumerate() {
+                assert_eq!(chars, encode_pair(start.wrapping_add(k as u16)));
+            }
+            let mut back = [0u8; 32];
+            let mut text = [0u8; 48];
+            text[..24].copy_from_slice(&dst);
+            text[24..].copy_from_slice(&dst);
+            assert!(decode_block(&text, &mut back));
+            assert_eq!(back[..16], src);
+            assert_eq!(back[16..], src);
+        }
+    }
+}
@@ -0,0 +1,206 @@
+//! SIMD128 encoder: 16 input bytes (8 groups) to 24 characters per step.
+//! SIMD128 decoder: 48 characters (16 groups) to 32 bytes per step.
+
+use core::arch::wasm32::*;
+
+use crate::{ALPHABET_64, DECODE_TABLE, DIV45_MUL, DIV45_SHIFT};
+
+/// Input bytes consumed per encode block.
+pub(crate) const ENCODE_BLOCK: usize = 16;
+/// Input characters consumed per decode block.
+pub(crate) const DECODE_BLOCK: usize = 48;
+
+/// Swaps the bytes of each 16-bit lane, between big-endian pairs and little-endian values.
+const SWAP: v128 = u8x16(1, 0, 3, 2, 5, 4, 7, 6, 9, 8, 11, 10, 13, 12, 15, 14);
+
+/// 16-byte row `k` of `table`, for `u8x16_swizzle`.
+const fn row(table: &[u8; 64], k: usize) -> v128 {
+    let t = table;
+    let o = 16 * k;
+    u8x16(
+        t[o],
+        t[o + 1],
+        t[o + 2],
+        t[o + 3],
+        t[o + 4],
+        t[o + 5],
+        t[o + 6],
+        t[o + 7],
+        t[o + 8],
+        t[o + 9],
+        t[o + 10],
+        t[o + 11],
+        t[o + 12],
+        t[o + 13],
+        t[o + 14],
+        t[o + 15],
+    )
+}
+
+/// Digit values of the characters `0x20..0x60`, indexed by `char - 0x20`.
+const DIGITS_FROM_SPACE: [u8; 64] = {
+    let mut table = [0; 64];
+    let mut i = 0;
+    while i < 64 {
+        table[i] = DECODE_TABLE[0x20 + i];
+        i += 1;
+    }
+    table
+};
+
+/// See [`super::encode_blocks`].
+#[inline]
+pub(crate) fn encode_blocks(input: &[u8], out: &mut [u8]) -> usize {
+    let mut n = 0;
+    for (block, dst) in input
+        .chunks_exact(ENCODE_BLOCK)
+        .zip(out.chunks_exact_mut(ENCODE_BLOCK / 2 * 3))
+    {
+        // SAFETY: `block` holds 16 readable bytes and `dst` 24 writable ones.
+        unsafe { encode_block(block.as_ptr(), dst.as_mut_ptr()) };
+        n += ENCODE_BLOCK;
+    }
+    n
+}
+
+/// Encode the 16 bytes at `src` into the 24 characters at `dst`.
+///
+/// # Safety
+///
+/// `src` must be valid for 16 reads and `dst` for 24 writes.
+#[inline]
+unsafe fn encode_block(src: *const u8, dst: *mut u8) {
+    // SAFETY: the caller guarantees 16 readable bytes; wasm loads need no alignment.
+    let bytes = unsafe { v128_load(src.cast()) };
+    // Big-endian byte pairs to 16-bit group values
+    let x = u8x16_swizzle(bytes, SWAP);
+    let q = div45(x);
+    let a = div45(q);
+    let c = i16x8_sub(x, i16x8_mul(q, u16x8_splat(45)));
+    let b = i16x8_sub(q, i16x8_mul(a, u16x8_splat(45)));
+    let cb = to_ascii(u8x16_narrow_i16x8(c, b));
+    let a = to_ascii(u8x16_narrow_i16x8(a, u16x8_splat(0)));
+    // Interleave `[c0..c7, b0..b7]` and `[a0..a7]` into `c0 b0 a0 c1 b1 a1 ...`
+    let lo = i8x16_shuffle::<0, 8, 16, 1, 9, 17, 2, 10, 18, 3, 11, 19, 4, 12, 20, 5>(cb, a);
+    let hi = i8x16_shuffle::<13, 21, 6, 14, 22, 7, 15, 23, 0, 0, 0, 0, 0, 0, 0, 0>(cb, a);
+    // SAFETY: the caller guarantees 24 writable bytes.
+    unsafe {
+        v128_store(dst.cast(), lo);
+        v128_store64_lane::<0>(hi, dst.add(16).cast());
+    }
+}
+
+/// `x / 45` in each 16-bit lane, by reciprocal multiplication.
+#[inline]
+fn div45(x: v128) -> v128 {
+    let m = u16x8_splat(DIV45_MUL as u16);
+    let lo = u32x4_shr(u32x4_extmul_low_u16x8(x, m), DIV45_SHIFT);
+    let hi = u32x4_shr(u32x4_extmul_high_u16x8(x, m), DIV45_SHIFT);
+    u16x8_narrow_i32x4(lo, hi)
+}
+
+/// Map each digit byte (0..45) to its character in the alphabet.
+#[inline]
+fn to_ascii(d: v128) -> v128 {
+    // `u8x16_swizzle` yields 0 for indices past 16, including those that wrapped below 0
+    let alphabet = |k: usize| {
+        u8x16_swizzle(
+            row(&ALPHABET_64, k),
+            u8x16_sub(d, u8x16_splat(16 * k as u8)),
+        )
+    };
+    v128_or(v128_or(alphabet(0), alphabet(1)), alphabet(2))
+}
+
+/// See [`super::decode_blocks`].
+#[inline]
+pub(crate) fn decode_blocks(input: &[u8], out: &mut [u8]) -> usize {
+    let mut n = 0;
+    for (block, dst) in input
+        .chunks_exact(DECODE_BLOCK)
+        .zip(out.chunks_exact_mut(DECODE_BLOCK / 3 * 2))
+    {
+        // SAFETY: `block` holds 48 readable bytes and `dst` 32 writable ones.
+        if !unsafe { decode_block(block.as_ptr(), dst.as_mut_ptr()) } {
+            break;
+        }
+        n += DECODE_BLOCK;
+    }
+    n
+}
+
+/// Decode the 48 characters at `src` into the 32 bytes at `dst`. Returns `false`, with `dst`
+/// unspecified, if any character is outside the alphabet or any group overflows.
+///
+/// # Safety
+///
+/// `src` must be valid for 48 reads and `dst` for 32 writes.
+#[inline]
+unsafe fn decode_block(src: *const u8, dst: *mut u8) -> bool {
+    // SAFETY: the caller guarantees 48 readable bytes.
+    let (v0, v1, v2) = unsafe {
+        (
+            v128_load(src.cast()),
+            v128_load(src.add(16).cast()),
+            v128_load(src.add(32).cast()),
+        )
+    };
+    let (v0, v1, v2) = (to_digits(v0), to_digits(v1), to_digits(v2));
+    // Digits are below 45 and invalid characters map to 0xFF, so the top bit flags them
+    if u8x16_bitmask(v128_or(v128_or(v0, v1), v2)) != 0 {
+        return false;
+    }
+    // Split the digits by position within their group: c (least significant), b, a
+    let c = i8x16_shuffle::<0, 3, 6, 9, 12, 15, 18, 21, 24, 27, 30, 0, 0, 0, 0, 0>(v0, v1);
+    let c = i8x16_shuffle::<0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 17, 20, 23, 26, 29>(c, v2);
+    let b = i8x16_shuffle::<1, 4, 7, 10, 13, 16, 19, 22, 25, 28, 31, 0, 0, 0, 0, 0>(v0, v1);
+    let b = i8x16_shuffle::<0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 18, 21, 24, 27, 30>(b, v2);
+    let a = i8x16_shuffle::<2, 5, 8, 11, 14, 17, 20, 23, 26, 29, 0, 0, 0, 0, 0, 0>(v0, v1);
+    let a = i8x16_shuffle::<0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 16, 19, 22, 25, 28, 31>(a, v2);
+    let half = |low: bool| {
+        let widen = |v| {
+            if low {
+                u16x8_extend_low_u8x16(v)
+            } else {
+                u16x8_extend_high_u8x16(v)
+            }
+        };
+        let (c, b, a) = (widen(c), widen(b), widen(a));
+        let t = i16x8_add(i16x8_mul(b, u16x8_splat(45)), c);
+        // The value fits 16 bits unless a > 32, or a == 32 and t > 65535 - 32 * 2025
+        let overflow = v128_or(
+            u16x8_gt(a, u16x8_splat(32)),
+            v128_and(
+                u16x8_eq(a, u16x8_splat(32)),
+                u16x8_gt(t, u16x8_splat(u16::MAX - 32 * 2025)),
+            ),
+        );
+        (i16x8_add(i16x8_mul(a, u16x8_splat(2025)), t), overflow)
+    };
+    let ((x_lo, over_lo), (x_hi, over_hi)) = (half(true), half(false));
+    if v128_any_true(v128_or(over_lo, over_hi)) {
+        return false;
+    }
+    // SAFETY: the caller guarantees 32 writable bytes.
+    unsafe {
+        v128_store(dst.cast(), u8x16_swizzle(x_lo, SWAP));
+        v128_store(dst.add(16).cast(), u8x16_swizzle(x_hi, SWAP));
+    }
+    true
+}
+
+/// Map each character to its digit value, or 0xFF if it is outside the alphabet.
+#[inline]
+fn to_digits(v: v128) -> v128 {
+    let index = u8x16_sub(v, u8x16_splat(0x20));
+    // `u8x16_swizzle` yields 0 for indices past 16, so only the matching row contributes
+    let digits = |k: usize| {
+        u8x16_swizzle(
+            row(&DIGITS_FROM_SPACE, k),
+            u8x16_sub(index, u8x16_splat(16 * k as u8)),
+        )
+    };
+    let digits = v128_or(v128_or(digits(0), digits(1)), v128_or(digits(2), digits(3)));
+    // Characters outside 0x20..0x60 match no row
+    v128_or(digits, u8x16_ge(index, u8x16_splat(64)))
+}
@@ -0,0 +1,298 @@
+//! SSSE3 encoder: 16 input bytes (8 groups) to 24 characters per step.
+//! AVX2 decoder: 48 characters (16 groups) to 32 bytes per step.
+
+// Register-only intrinsics are safe inside `#[target_feature]` functions from Rust 1.87 on; the
+// `unsafe` blocks around them are for the 1.85 MSRV.
+#![allow(unused_unsafe)]
+
+use core::arch::x86_64::*;
+
+use crate::{DECODE_TABLE, DIV45_MUL, DIV45_SHIFT};
+
+/// Input bytes consumed per encode block.
+pub(crate) const ENCODE_BLOCK: usize = 16;
+/// Input characters consumed per decode block.
+pub(crate) const DECODE_BLOCK: usize = 48;
+
+/// Characters for digits 36..=44, indexed by `digit - 36`.
+const SPECIALS: [u8; 16] = *b" $%*+-./:\0\0\0\0\0\0\0";
+
+/// `pshufb` masks interleaving `[c0..c7, b0..b7]` and `[a0..a7]` into `c0 b0 a0 c1 b1 a1 ...`:
+/// the first 16 output characters, then the last 8. `0x80` selects zero.
+const INTERLEAVE: [[u8; 16]; 4] = {
+    let mut masks = [[0x80; 16]; 4];
+    let mut pos = 0;
+    while pos < 24 {
+        let (group, digit) = (pos / 3, pos % 3);
+        let (half, i) = (pos / 16, pos % 16);
+        // `c` and `b` come from the first register, `a` from the second
+        match digit {
+            0 => masks[half * 2][i] = group as u8,
+            1 => masks[half * 2][i] = 8 + group as u8,
+            _ => masks[half * 2 + 1][i] = group as u8,
+        }
+        pos += 1;
+    }
+    masks
+};
+
+/// See [`super::encode_blocks`].
+///
+/// # Safety
+///
+/// The CPU must support SSSE3.
+#[inline]
+#[target_feature(enable = "ssse3")]
+pub(crate) unsafe fn encode_blocks(input: &[u8], out: &mut [u8]) -> usize {
+    let mut n = 0;
+    for (block, dst) in input
+        .chunks_exact(ENCODE_BLOCK)
+        .zip(out.chunks_exact_mut(ENCODE_BLOCK / 2 * 3))
+    {
+        // SAFETY: `block` holds 16 readable bytes and `dst` 24 writable ones.
+        unsafe { encode_block(block.as_ptr(), dst.as_mut_ptr()) };
+        n += ENCODE_BLOCK;
+    }
+    n
+}
+
+/// Encode the 16 bytes at `src` into the 24 characters at `dst`.
+#[inline]
+#[target_feature(enable = "ssse3")]
+unsafe fn encode_block(src: *const u8, dst: *mut u8) {
+    // SAFETY: the caller guarantees 16 readable bytes; unaligned loads are allowed.
+    let bytes = unsafe { _mm_loadu_si128(src.cast()) };
+    // SAFETY: SSSE3 is enabled for this function and the caller's CPU supports it.
+    let (cb, a) = unsafe {
+        let swap = _mm_setr_epi8(1, 0, 3, 2, 5, 4, 7, 6, 9, 8, 11, 10, 13, 12, 15, 14);
+        let mul = _mm_set1_epi16(DIV45_MUL as i16);
+        let m45 = _mm_set1_epi16(45);
+        // Big-endian byte pairs to 16-bit group values
+        let x = _mm_shuffle_epi8(bytes, swap);
+        let q = _mm_srli_epi16::<{ DIV45_SHIFT as i32 - 16 }>(_mm_mulhi_epu16(x, mul));
+        let c = _mm_sub_epi16(x, _mm_mullo_epi16(q, m45));
+        let a = _mm_srli_epi16::<{ DIV45_SHIFT as i32 - 16 }>(_mm_mulhi_epu16(q, mul));
+        let b = _mm_sub_epi16(q, _mm_mullo_epi16(a, m45));
+        (
+            digits_to_ascii(_mm_packus_epi16(c, b)),
+            digits_to_ascii(_mm_packus_epi16(a, _mm_setzero_si128())),
+        )
+    };
+    // SAFETY: each mask is a 16-byte array, and SSSE3 is available as above.
+    let mix = |k: usize, v: __m128i| unsafe {
+        _mm_shuffle_epi8(v, _mm_loadu_si128(INTERLEAVE[k].as_ptr().cast()))
+    };
+    // SAFETY: SSSE3 is available as above.
+    let (lo, hi) = unsafe {
+        (
+            _mm_or_si128(mix(0, cb), mix(1, a)),
+            _mm_or_si128(mix(2, cb), mix(3, a)),
+        )
+    };
+    // SAFETY: the caller guarantees 24 writable bytes.
+    unsafe {
+        _mm_storeu_si128(dst.cast(), lo);
+        _mm_storel_epi64(dst.add(16).cast(), hi);
+    }
+}
+
+/// Map each digit byte (0..45) to its character in the alphabet.
+///
+/// # Safety
+///
+/// The CPU must support SSSE3.
+#[inline]
+#[target_feature(enable = "ssse3")]
+unsafe fn digits_to_ascii(v: __m128i) -> __m128i {
+    // SAFETY: SSSE3 is enabled for this function and the caller's CPU supports it; SPECIALS is a
+    // 16-byte array.
+    unsafe {
+        // '0' + v below 10, 'A' - 10 + v below 36, the specials table from 36
+        let below10 = _mm_cmpgt_epi8(_mm_set1_epi8(10), v);
+        let below36 = _mm_cmpgt_epi8(_mm_set1_epi8(36), v);
+        let offset = _mm_add_epi8(
+            _mm_set1_epi8(b'A' as i8 - 10),
+            _mm_and_si128(below10, _mm_set1_epi8(b'0' as i8 - (b'A' as i8 - 10))),
+        );
+        let alnum = _mm_and_si128(_mm_add_epi8(v, offset), below36);
+        // Below 36, `v - 36` has the top bit set, which makes `pshufb` produce 0
+        let specials = _mm_loadu_si128(SPECIALS.as_ptr().cast());
+        let special = _mm_shuffle_epi8(specials, _mm_sub_epi8(v, _mm_set1_epi8(36)));
+        _mm_or_si128(alnum, special)
+    }
+}
+
+/// Digit values of the characters `0x20..0x60`, one 16-entry row per high nibble, for `pshufb`.
+const NIBBLE_ROWS: [[u8; 16]; 4] = {
+    let mut rows = [[0; 16]; 4];
+    let mut i = 0;
+    while i < 64 {
+        rows[i / 16][i % 16] = DECODE_TABLE[0x20 + i];
+        i += 1;
+    }
+    rows
+};
+
+/// `pshufb` masks gathering digit `k` (0 = least significant) of each group of a 256-bit lane
+/// pair into the low byte of a 16-bit lane. Each 128-bit lane decodes 24 characters: the first
+/// 16 come from `lo`, the last 8 from `hi`, which holds them at byte 0 in the first lane and at
+/// byte 8 in the second. Indexed `[k][0 for lo, 1 for hi]`; `0x80` selects zero.
+const GATHER: [[[u8; 32]; 2]; 3] = {
+    let mut masks = [[[0x80; 32]; 2]; 3];
+    let mut k = 0;
+    while k < 3 {
+        let mut lane = 0;
+        while lane < 2 {
+            let mut g = 0;
+            while g < 8 {
+                let p = 3 * g + k;
+                let at = lane * 16 + 2 * g;
+                if p < 16 {
+                    masks[k][0][at] = p as u8;
+                } else {
+                    masks[k][1][at] = (p - 16 + lane * 8) as u8;
+                }
+                g += 1;
+            }
+            lane += 1;
+        }
+        k += 1;
+    }
+    masks
+};
+
+/// Movemask bits of the characters that belong to the block: all of `lo`, and in `hi` the low
+/// 8 bytes of the first lane and the high 8 of the second.
+const HI_USED: i32 = 0xFF00_00FF_u32 as i32;
+
+/// See [`super::decode_blocks`].
+///
+/// # Safety
+///
+/// The CPU must support AVX2.
+#[inline]
+#[target_feature(enable = "avx2")]
+pub(crate) unsafe fn decode_blocks(input: &[u8], out: &mut [u8]) -> usize {
+    let mut n = 0;
+    for (block, dst) in input
+        .chunks_exact(DECODE_BLOCK)
+        .zip(out.chunks_exact_mut(DECODE_BLOCK / 3 * 2))
+    {
+        // SAFETY: `block` holds 48 readable bytes and `dst` 32 writable ones.
+        if !unsafe { decode_block(block.as_ptr(), dst.as_mut_ptr()) } {
+            break;
+        }
+        n += DECODE_BLOCK;
+    }
+    n
+}
+
+/// Decode the 48 characters at `src` into the 32 bytes at `dst`. Returns `false`, with `dst`
+/// unspecified, if any character is outside the alphabet or any group overflows.
+#[inline]
+#[target_feature(enable = "avx2")]
+unsafe fn decode_block(src: *const u8, dst: *mut u8) -> bool {
+    // SAFETY: the caller guarantees 48 readable bytes; unaligned loads are allowed.
+    let (lo, hi) = unsafe {
+        (
+            _mm256_loadu2_m128i(src.add(24).cast(), src.cast()),
+            _mm256_loadu2_m128i(src.add(32).cast(), src.add(16).cast()),
+        )
+    };
+    // SAFETY: AVX2 is enabled for this function and the caller's CPU supports it.
+    let (lo, hi) = unsafe { (to_digits(lo), to_digits(hi)) };
+    // Digits are below 45 and invalid characters map to 0xFF, so the top bit flags them
+    // SAFETY: AVX2 is available as above.
+    let invalid = unsafe { _mm256_movemask_epi8(lo) | (_mm256_movemask_epi8(hi) & HI_USED) };
+    // SAFETY: each mask is a 32-byte array, and AVX2 is available as above.
+    let load = |m: &[u8; 32]| unsafe { _mm256_loadu_si256(m.as_ptr().cast()) };
+    // SAFETY: AVX2 is available as above.
+    let gather = |k: usize| unsafe {
+        _mm256_or_si256(
+            _mm256_shuffle_epi8(lo, load(&GATHER[k][0])),
+            _mm256_shuffle_epi8(hi, load(&GATHER[k][1])),
+        )
+    };
+    let (c, b, a) = (gather(0), gather(1), gather(2));
+    // SAFETY: AVX2 is available as above.
+    let (t, overflow) = unsafe {
+        // The value a * 2025 + t fits 16 bits unless a > 32, or a == 32 and t > 65535 - 32 * 2025
+        let t = _mm256_add_epi16(_mm256_mullo_epi16(b, _mm256_set1_epi16(45)), c);
+        let overflow = _mm256_or_si256(
+            _mm256_cmpgt_epi16(a, _mm256_set1_epi16(32)),
+            _mm256_and_si256(
+                _mm256_cmpeq_epi16(a, _mm256_set1_epi16(32)),
+                _mm256_cmpgt_epi16(t, _mm256_set1_epi16((u16::MAX - 32 * 2025) as i16)),
+            ),
+        );
+        (t, _mm256_movemask_epi8(overflow))
+    };
+    if invalid != 0 || overflow != 0 {
+        return false;
+    }
+    // SAFETY: AVX2 is available as above, and the caller guarantees 32 writable bytes.
+    unsafe {
+        let x = _mm256_add_epi16(_mm256_mullo_epi16(a, _mm256_set1_epi16(2025)), t);
+        // 16-bit values to big-endian byte pairs
+        let swap = _mm256_setr_epi8(
+            1, 0, 3, 2, 5, 4, 7, 6, 9, 8, 11, 10, 13, 12, 15, 14, 1, 0, 3, 2, 5, 4, 7, 6, 9, 8, 11,
+            10, 13, 12, 15, 14,
+        );
+        _mm256_storeu_si256(dst.cast(), _mm256_shuffle_epi8(x, swap));
+    }
+    true
+}
+
+/// Map each character to its digit value, or 0xFF if it is outside the alphabet.
+///
+/// # Safety
+///
+/// The CPU must support AVX2.
+#[inline]
+#[target_feature(enable = "avx2")]
+unsafe fn to_digits(v: __m256i) -> __m256i {
+    // SAFETY: AVX2 is enabled for this function and the caller's CPU supports it; each row of
+    // NIBBLE_ROWS is a 16-byte array.
+    unsafe {
+        let low = _mm256_and_si256(v, _mm256_set1_epi8(0x0F));
+        let high = _mm256_and_si256(_mm256_srli_epi16::<4>(v), _mm256_set1_epi8(0x0F));
+        let mut digits = _mm256_setzero_si256();
+        let mut matched = _mm256_setzero_si256();
+        for (row, nibble) in NIBBLE_ROWS.iter().zip(2..) {
+            let row = _mm256_broadcastsi128_si256(_mm_loadu_si128(row.as_ptr().cast()));
+            let here = _mm256_cmpeq_epi8(high, _mm256_set1_epi8(nibble));
+            digits = _mm256_or_si256(
+                digits,
+                _mm256_and_si256(here, _mm256_shuffle_epi8(row, low)),
+            );
+            matched = _mm256_or_si256(matched, here);
+        }
+        // Characters outside 0x20..0x60 match no row
+        _mm256_or_si256(digits, _mm256_andnot_si256(matched, _mm256_set1_epi8(-1)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::encode_pair;
+
+    #[test]
+    fn encode_block_matches_scalar() {
+        if !std::is_x86_feature_detected!("ssse3") {
+            return;
+        }
+        for start in (0..=u16::MAX).step_by(8) {
+            let mut src = [0u8; 16];
+            for (k, pair) in src.chunks_exact_mut(2).enumerate() {
+                pair.copy_from_slice(&start.wrapping_add(k as u16).to_be_bytes());
+            }
+            let mut dst = [0u8; 24];
+            // SAFETY: SSSE3 was detected above; both buffers are large enough.
+            unsafe { encode_block(src.as_ptr(), dst.as_mut_ptr()) };
+            for (k, chars) in dst.chunks_exact(3).enumerate() {
+                assert_eq!(chars, encode_pair(start.wrapping_add(k as u16)));
+            }
+        }
+    }
+}
@@ -0,0 +1,288 @@
+//! Sans-IO state machines for input that arrives in pieces.
+
+use crate::iter::EncodeIter;
+use crate::{Base45Error, b45_val, decode_tail, decode_triple, encode_pair, encode_single};
+
+/// Incremental encoder for input split into arbitrary chunks.
+///
+/// Each [`push`](StreamEncoder::push) yields the characters of every complete 2-byte group seen
+/// so far; an odd trailing byte is held back until the next chunk or [`finish`](StreamEncoder::finish).
+/// The concatenated output equals [`encode`](crate::encode) of the concatenated input.
+///
+/// ```
+/// use qr_base45::StreamEncoder;
+///
+/// let mut enc = StreamEncoder::new();
+/// let mut out: String = enc.push(b"ie").collect();
+/// out.extend(enc.push(b"tf!"));
+/// out.extend(enc.finish());
+/// assert_eq!(out, "QED8WEX0");
+/// ```
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct StreamEncoder {
+    pending: Option<u8>,
+}
+
+impl StreamEncoder {
+    /// A fresh encoder with nothing buffered.
+    pub const fn new() -> StreamEncoder {
+        StreamEncoder { pending: None }
+    }
+
+    /// Feed the next chunk, returning the characters it completes.
+    ///
+    /// An odd trailing byte is buffered before `push` returns, not as the iterator is consumed.
+    pub fn push<'a>(&mut self, input: &'a [u8]) -> EncodeIter<'a> {
+        let (head, len, input) = match (self.pending, input) {
+            (Some(u), [v, rest @ ..]) => {
+                self.pending = None;
+                (encode_pair((u as u16) * 256 + (*v as u16)), 3, rest)
+            }
+            _ => ([0; 3], 0, input),
+        };
+        let input = match input {
+            [rest @ .., last] if input.len() % 2 == 1 => {
+                self.pending = Some(*last);
+                rest
+            }
+            _ => input,
+        };
+        EncodeIter::with_head(head, len, input)
+    }
+
+    /// End the stream, returning the 2 characters of a buffered trailing byte, if any.
+    ///
+    /// The encoder is reset and can be reused for a new stream.
+    pub fn finish(&mut self) -> EncodeIter<'static> {
+        match self.pending.take() {
+            Some(u) => {
+                let [b, a] = encode_single(u);
+                EncodeIter::with_head([b, a, 0], 2, &[])
+            }
+            None => EncodeIter::with_head([0; 3], 0, &[]),
+        }
+    }
+
+    /// Whether a trailing byte is waiting for its partner.
+    pub const fn has_pending(&self) -> bool {
+        self.pending.is_some()
+    }
+}
+
+/// Incremental decoder for Base45 text split into arbitrary chunks, e.g. packets off a serial link.
+///
+/// Characters are validated as they arrive and each byte is yielded as soon as its 3-character
+/// group is complete, even when the group spans several chunks. The final partial group is checked
+/// by [`finish`](StreamDecoder::finish). Error positions are byte offsets from the start of the
+/// stream.
+///
+/// Once an error is reported the decoder stays failed: later chunks yield the same error and
+/// decode nothing, until [`finish`](StreamDecoder::finish) returns it and resets the decoder.
+///
+/// ```
+/// use qr_base45::StreamDecoder;
+///
+/// let mut dec = StreamDecoder::new();
+/// let mut out = Vec::new();
+/// for chunk in [&b"QE"[..], b"D8W", b"EX0"] {
+///     for b in dec.push(chunk) {
+///         out.push(b?);
+///     }
+/// }
+/// out.extend(dec.finish()?);
+/// assert_eq!(out, b"ietf!");
+/// # Ok::<(), qr_base45::Base45Error>(())
+/// ```
+#[derive(Debug, Clone, Copy, Default)]
+pub struct StreamDecoder {
+    group: [u8; 3],
+    len: usize,
+    offset: usize,
+    error: Option<Base45Error>,
+}
+
+impl StreamDecoder {
+    /// A fresh decoder with nothing buffered.
+    pub const fn new() -> StreamDecoder {
+        StreamDecoder {
+            group: [0; 3],
+            len: 0,
+            offset: 0,
+            error: None,
+        }
+    }
+
+    /// Feed the next chunk, returning the bytes it completes.
+    ///
+    /// The chunk is consumed as the iterator advances: drain it before pushing the next chunk, or
+    /// the characters it has not reached are lost.
+    pub fn push<'a>(&'a mut self, input: &'a [u8]) -> StreamBytes<'a> {
+        StreamBytes {
+            dec: self,
+            input,
+            lo: None,
+            done: false,
+        }
+    }
+
+    /// End the stream, decoding the buffered partial group (1 or 2 characters) if any.
+    ///
+    /// Returns the byte of a 2-character final group, or the error that failed the stream. The
+    /// decoder is reset either way and can be reused for a new stream.
+    pub fn finish(&mut self) -> Result<Option<u8>, Base45Error> {
+        let dec = core::mem::take(self);
+        if let Some(e) = dec.error {
+            return Err(e);
+        }
+        match dec.group.get(..dec.len) {
+            Some(tail @ [_, ..]) => decode_tail(tail, dec.offset - dec.len).map(Some),
+            _ => Ok(None),
+        }
+    }
+
+    /// Number of characters buffered towards the next group (0 to 2).
+    pub const fn buffered(&self) -> usize {
+        self.len
+    }
+
+    /// Number of characters pushed so far, which is the stream offset of the next chunk.
+    #[cfg(feature = "futures")]
+    pub(crate) const fn offset(&self) -> usize {
+        self.offset
+    }
+}
+
+/// Iterator over the bytes decoded from one chunk. Created by [`StreamDecoder::push`].
+///
+/// The first error ends iteration.
+#[derive(Debug)]
+pub struct StreamBytes<'a> {
+    dec: &'a mut StreamDecoder,
+    input: &'a [u8],
+    lo: Option<u8>,
+    done: bool,
+}
+
+impl Iterator for StreamBytes<'_> {
+    type Item = Result<u8, Base45Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if let Some(b) = self.lo.take() {
+            return Some(Ok(b));
+        }
+        if self.done {
+            return None;
+        }
+        if let Some(e) = self.dec.error {
+            self.done = true;
+            return Some(Err(e));
+        }
+        let dec = &mut *self.dec;
+        while let [b, ref rest @ ..] = *self.input {
+            self.input = rest;
+            if b45_val(b).is_none() {
+                dec.error = Some(Base45Error::invalid_byte(b, dec.offset));
+                break;
+            }
+            dec.group[dec.len] = b;
+            dec.len += 1;
+            dec.offset += 1;
+            if dec.len == 3 {
+                dec.len = 0;
+                match decode_triple(&dec.group, dec.offset - 3) {
+                    Ok([hi, lo]) => {
+                        self.lo = Some(lo);
+                        return Some(Ok(hi));
+                    }
+                    Err(e) => {
+                        dec.error = Some(e);
+                        break;
+                    }
+                }
+            }
+        }
+        self.done = true;
+        dec.error.map(Err)
+    }
+}
+
+impl core::iter::FusedIterator for StreamBytes<'_> {}
+
+#[cfg(all(test, feature = "alloc"))]
+mod tests {
+    use super::*;
+    use crate::ErrorKind;
+    use alloc::string::String;
+    use alloc::vec::Vec;
+
+    #[test]
+    fn stream_encoder_matches_encode_for_any_split() {
+        let data: &[u8] = b"Hello, Base45 streams!!";
+        for a in 0..=data.len() {
+            for b in a..=data.len() {
+                let mut enc = StreamEncoder::new();
+                let mut out = String::new();
+                for chunk in [&data[..a], &data[a..b], &data[b..]] {
+                    out.extend(enc.push(chunk));
+                }
+                out.extend(enc.finish());
+                assert_eq!(out, crate::encode(data), "split at {a}, {b}");
+                assert!(!enc.has_pending());
+            }
+        }
+    }
+
+    #[test]
+    fn stream_encoder_buffers_odd_byte() {
+        let mut enc = StreamEncoder::new();
+        assert_eq!(enc.push(b"A").len(), 0);
+        assert!(enc.has_pending());
+        assert_eq!(enc.push(b"").len(), 0);
+        let it = enc.push(b"B");
+        assert_eq!(it.len(), 3);
+        assert_eq!(it.collect::<String>(), crate::encode(b"AB"));
+        assert_eq!(enc.finish().len(), 0);
+    }
+
+    #[test]
+    fn stream_decoder_matches_decode_for_any_split() {
+        let s = crate::encode(b"Hello, Base45 streams!!");
+        let s = s.as_bytes();
+        for a in 0..=s.len() {
+            for b in a..=s.len() {
+                let mut dec = StreamDecoder::new();
+                let mut out = Vec::new();
+                for chunk in [&s[..a], &s[a..b], &s[b..]] {
+                    for byte in dec.push(chunk) {
+                        out.push(byte.unwrap());
+                    }
+                }
+                out.extend(dec.finish().unwrap());
+                assert_eq!(out, b"Hello, Base45 streams!!", "split at {a}, {b}");
+            }
+        }
+    }
+
+    #[test]
+    fn stream_decoder_errors() {
+        // A bad character is reported as soon as it arrives, at its stream offset
+        let mut dec = StreamDecoder::new();
+        assert_eq!(dec.push(b"QE").count(), 0);
+        assert_eq!(dec.buffered(), 2);
+        let err = dec.push(b"D8w").last().unwrap().unwrap_err();
+        assert_eq!((err.kind, err.position), (ErrorKind::InvalidByte, 4));
+        // The failure is sticky until `finish` hands it back
+        assert_eq!(dec.push(b"EX0").next().unwrap().unwrap_err(), err);
+        assert_eq!(dec.finish().unwrap_err(), err);
+        assert_eq!(dec.finish(), Ok(None));
+
+        dec.push(b"QED8").for_each(drop);
+        assert_eq!(dec.finish().unwrap_err().kind, ErrorKind::Dangling);
+        dec.push(b"GGW").for_each(drop);
+        assert_eq!(dec.finish().unwrap_err().kind, ErrorKind::Overflow);
+        dec.push(b"BB8").for_each(drop);
+        assert_eq!(dec.push(b"ZZ").count(), 0);
+        let err = dec.finish().unwrap_err();
+        assert_eq!((err.kind, err.position), (ErrorKind::TailOverflow, 3));
+    }
+}
@@ -0,0 +1,130 @@
+//! Validated Base45 text.
+
+use alloc::borrow::ToOwned;
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::fmt;
+use core::ops::Deref;
+use core::str::FromStr;
+
+use crate::{Base45Error, char_err, check_groups};
+
+/// A string that is known to be valid Base45 (RFC 9285).
+/// It can only be built from text that decodes successfully, or by encoding bytes,
+/// so holders can decode it without handling errors again.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(
+    feature = "diesel",
+    derive(diesel::expression::AsExpression, diesel::deserialize::FromSqlRow),
+    diesel(sql_type = diesel::sql_types::Text)
+)]
+pub struct Base45String(pub(crate) String);
+
+impl Base45String {
+    /// Encode arbitrary bytes into a validated Base45 string.
+    pub fn encode<T: AsRef<[u8]>>(input: T) -> Self {
+        Base45String(crate::encode(input))
+    }
+
+    /// Borrow the encoded text.
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+
+    /// Unwrap into the underlying `String`.
+    pub fn into_string(self) -> String {
+        self.0
+    }
+
+    /// Decode back to raw bytes. Cannot fail since the contents were validated on construction.
+    pub fn decode(&self) -> Vec<u8> {
+        crate::decode(&self.0).expect("Base45String holds validated text")
+    }
+}
+
+impl FromStr for Base45String {
+    type Err = Base45Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        check_groups(s.as_bytes()).map_err(char_err(s.as_bytes()))?;
+        Ok(Base45String(s.to_owned()))
+    }
+}
+
+impl TryFrom<&str> for Base45String {
+    type Error = Base45Error;
+
+    fn try_from(s: &str) -> Result<Self, Self::Error> {
+        s.parse()
+    }
+}
+
+impl TryFrom<String> for Base45String {
+    type Error = Base45Error;
+
+    fn try_from(s: String) -> Result<Self, Self::Error> {
+        check_groups(s.as_bytes()).map_err(char_err(s.as_bytes()))?;
+        Ok(Base45String(s))
+    }
+}
+
+impl Deref for Base45String {
+    type Target = str;
+
+    fn deref(&self) -> &str {
+        &self.0
+    }
+}
+
+impl AsRef<str> for Base45String {
+    fn as_ref(&self) -> &str {
+        &self.0
+    }
+}
+
+impl fmt::Display for Base45String {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl From<Base45String> for String {
+    fn from(s: Base45String) -> String {
+        s.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ErrorKind;
+
+    #[test]
+    fn parses_only_valid_text() {
+        let s: Base45String = "QED8WEX0".parse().unwrap();
+        assert_eq!(s.decode(), b"ietf!");
+        assert_eq!(s.len(), 8); // via Deref<Target = str>
+        assert_eq!(s.to_string(), "QED8WEX0");
+
+        assert!(matches!(
+            "BB8A".parse::<Base45String>(),
+            Err(e) if e.kind == ErrorKind::Dangling
+        ));
+        assert!(matches!(
+            Base45String::try_from("ZZ"),
+            Err(e) if e.kind == ErrorKind::TailOverflow
+        ));
+        assert!(matches!(
+            Base45String::try_from(String::from("bb8")),
+            Err(e) if e.kind == ErrorKind::InvalidChar
+        ));
+    }
+
+    #[test]
+    fn encode_roundtrips() {
+        let s = Base45String::encode(b"Hello!!");
+        assert_eq!(s.as_str(), "%69 VD92EX0");
+        assert_eq!(Base45String::try_from(s.as_str()).unwrap(), s);
+        assert_eq!(s.decode(), b"Hello!!");
+    }
+}
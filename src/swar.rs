@@ -0,0 +1,134 @@
+//! Two groups per step in 64-bit arithmetic: each group value sits in its own 32-bit lane, and
+//! division by 45 is a multiply and shift, so no lane ever carries into the other.
+
+use crate::{ALPHABET_64, Base45Error, DECODE_TABLE, DIV45_MUL, DIV45_SHIFT, decode_triple};
+
+/// [`DIV45_MUL`] widened for 64-bit lanes.
+const DIV45_MUL_64: u64 = DIV45_MUL as u64;
+/// Lane masks for a group value divided by 45 (< 1457), and divided again (< 33).
+const LANES_11: u64 = 0x0000_07FF_0000_07FF;
+const LANES_6: u64 = 0x0000_003F_0000_003F;
+/// Lane bits above a 16-bit group value.
+const LANES_OVER_16: u64 = 0xFFFF_0000_FFFF_0000;
+
+/// Encode 4 input bytes (2 groups) into their 6 Base45 characters. Unused with `big-tables`.
+#[cfg_attr(feature = "big-tables", allow(dead_code))]
+#[inline(always)]
+pub(crate) fn encode_quad(input: &[u8; 4]) -> [u8; 6] {
+    let x = (u16::from_be_bytes([input[0], input[1]]) as u64) << 32
+        | u16::from_be_bytes([input[2], input[3]]) as u64;
+    let q = ((x * DIV45_MUL_64) >> DIV45_SHIFT) & LANES_11;
+    let c = x - q * 45;
+    let a = ((q * DIV45_MUL_64) >> DIV45_SHIFT) & LANES_6;
+    let b = q - a * 45;
+    let ch = |lane: u64| ALPHABET_64[(lane & 0x3F) as usize];
+    [ch(c >> 32), ch(b >> 32), ch(a >> 32), ch(c), ch(b), ch(a)]
+}
+
+/// Input characters per [`decode_chunk`] call.
+pub(crate) const CHUNK: usize = 48;
+
+/// Decode 6 Base45 characters (2 full groups) starting at input offset `at` into their 4 bytes.
+///
+/// Any failure is handed to [`decode_triple`], so errors are exactly those of the scalar path.
+#[inline(always)]
+pub(crate) fn decode_sextet(group: &[u8; 6], at: usize) -> Result<[u8; 4], Base45Error> {
+    let (bytes, bad) = sextet_bytes(group);
+    if bad != 0 {
+        return scalar_sextet(group, at);
+    }
+    Ok(bytes)
+}
+
+/// Decode [`CHUNK`] characters (16 groups) starting at input offset `at` into `out`. The
+/// alphabet and overflow checks of every group are OR-ed together and tested once, so valid
+/// input runs without a data-dependent branch; on failure the chunk is decoded again group by
+/// group to find the exact error, leaving `out` unspecified.
+#[inline(always)]
+pub(crate) fn decode_chunk(
+    chunk: &[u8; CHUNK],
+    out: &mut [u8; CHUNK / 3 * 2],
+    at: usize,
+) -> Result<(), Base45Error> {
+    let mut bad = 0;
+    // Indexed rather than zipped chunk iterators, which the optimizer does not fully unroll
+    for k in 0..CHUNK / 6 {
+        if let Ok(group) = chunk[6 * k..6 * k + 6].try_into() {
+            let (bytes, flags) = sextet_bytes(group);
+            out[4 * k..4 * k + 4].copy_from_slice(&bytes);
+            bad |= flags;
+        }
+    }
+    if bad != 0 {
+        for (k, group) in chunk.chunks_exact(6).enumerate() {
+            if let Ok(group) = group.try_into() {
+                scalar_sextet(group, at + 6 * k)?;
+            }
+        }
+    }
+    Ok(())
+}
+
+/// The 4 bytes of a sextet, assuming it is valid, and a flag word that is nonzero if it is not.
+#[inline(always)]
+fn sextet_bytes(group: &[u8; 6]) -> ([u8; 4], u64) {
+    let d = group.map(|b| DECODE_TABLE[b as usize] as u64);
+    // Digits are below 45 and INVALID_DIGIT has the top bit set, so one test covers all six
+    let invalid = (d[0] | d[1] | d[2] | d[3] | d[4] | d[5]) & 0x80;
+    let x = (d[0] << 32 | d[3]) + (d[1] << 32 | d[4]) * 45 + (d[2] << 32 | d[5]) * (45 * 45);
+    let bytes = [(x >> 40) as u8, (x >> 32) as u8, (x >> 8) as u8, x as u8];
+    (bytes, invalid | x & LANES_OVER_16)
+}
+
+/// Decode the two groups of a sextet one at a time.
+#[inline(always)]
+fn scalar_sextet(group: &[u8; 6], at: usize) -> Result<[u8; 4], Base45Error> {
+    let [a, b] = decode_triple(&group[..3], at)?;
+    let [c, d] = decode_triple(&group[3..], at + 3)?;
+    Ok([a, b, c, d])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{encode_pair, pair_value};
+
+    #[test]
+    fn matches_scalar_on_every_group_value() {
+        for x in 0..=u16::MAX {
+            let y = x.rotate_left(7) ^ 0x5A5A;
+            let [a, b] = x.to_be_bytes();
+            let [c, d] = y.to_be_bytes();
+            let quad = encode_quad(&[a, b, c, d]);
+            assert_eq!(quad[..3], encode_pair(x));
+            assert_eq!(quad[3..], encode_pair(y));
+            assert_eq!(decode_sextet(&quad, 0), Ok([a, b, c, d]));
+        }
+        // Overflow in either lane is reported at its own group
+        let err = decode_sextet(b"BB8:::", 6).unwrap_err();
+        assert_eq!(err, pair_value(44, 44, 44, 9).unwrap_err());
+        let err = decode_sextet(b":::BB8", 0).unwrap_err();
+        assert_eq!(err.position, 0);
+        assert_eq!(decode_sextet(b"BB8a::", 0).unwrap_err().position, 3);
+    }
+
+    #[test]
+    fn chunk_errors_match_scalar() {
+        let text: [u8; CHUNK] = crate::encode_array(&[0xA5; 32]);
+        let mut out = [0u8; 32];
+        assert_eq!(decode_chunk(&text, &mut out, 0), Ok(()));
+        assert_eq!(out, [0xA5; 32]);
+        for at in [0, 7, 23, 47] {
+            let mut bad = text;
+            bad[at] = b'a';
+            let err = decode_chunk(&bad, &mut out, 96).unwrap_err();
+            assert_eq!(err, Base45Error::invalid_byte(b'a', 96 + at));
+        }
+        // The first failing group wins, whatever its kind
+        let mut bad = text;
+        bad[30..33].copy_from_slice(b":::");
+        bad[40] = b'~';
+        let err = decode_chunk(&bad, &mut out, 0).unwrap_err();
+        assert_eq!((err.kind, err.position), (crate::ErrorKind::Overflow, 30));
+    }
+}
@@ -0,0 +1,81 @@
+//! `proptest` strategies for property-testing code built on Base45: raw payloads, their valid
+//! encodings, and encodings broken by a single fault.
+//!
+//! Every strategy shrinks towards shorter payloads.
+//!
+//! ```
+//! use proptest::prelude::*;
+//! use qr_base45::testing;
+//!
+//! proptest!(|(case in testing::almost_valid())| {
+//!     let err = qr_base45::decode(&case.text).unwrap_err();
+//!     prop_assert_eq!((err.kind, err.position), (case.kind, case.position));
+//! });
+//! ```
+
+use alloc::vec::Vec;
+
+use proptest::collection::vec;
+use proptest::prelude::*;
+
+use crate::{AlmostValid, Base45String};
+
+/// Longest payload the strategies generate.
+const MAX_LEN: usize = 256;
+
+/// Arbitrary byte payloads of up to 256 bytes.
+pub fn payloads() -> impl Strategy<Value = Vec<u8>> {
+    vec(any::<u8>(), 0..=MAX_LEN)
+}
+
+/// Valid Base45 text: the encodings of [`payloads`].
+pub fn encoded() -> impl Strategy<Value = Base45String> {
+    payloads().prop_map(Base45String::encode)
+}
+
+/// Valid Base45 text with one injected fault, each [`ErrorKind`](crate::ErrorKind) that fits the
+/// text equally likely.
+pub fn almost_valid() -> impl Strategy<Value = AlmostValid> {
+    (encoded(), any::<u32>(), any::<u32>(), any::<u32>())
+        .prop_map(|(text, choice, pick, value)| AlmostValid::inject(text, choice, pick, value))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ErrorKind;
+    use crate::fault::FAULTS;
+
+    proptest! {
+        #[test]
+        fn encoded_round_trips(data in payloads()) {
+            let text = Base45String::encode(&data);
+            prop_assert_eq!(crate::decode(&text).unwrap(), data);
+        }
+
+        #[test]
+        fn encoded_is_valid(text in encoded()) {
+            prop_assert!(crate::is_valid(&text));
+        }
+
+        #[test]
+        fn almost_valid_fails_where_injected(case in almost_valid()) {
+            let err = crate::decode(&case.text).unwrap_err();
+            prop_assert_eq!((err.kind, err.position), (case.kind, case.position));
+        }
+    }
+
+    #[test]
+    fn almost_valid_covers_every_fault() {
+        use proptest::strategy::ValueTree;
+        use proptest::test_runner::TestRunner;
+
+        let mut runner = TestRunner::deterministic();
+        let mut seen: Vec<ErrorKind> = Vec::new();
+        for _ in 0..200 {
+            let case = almost_valid().new_tree(&mut runner).unwrap().current();
+            seen.push(case.kind);
+        }
+        assert!(FAULTS.iter().all(|kind| seen.contains(kind)), "{seen:?}");
+    }
+}
@@ -0,0 +1,249 @@
+//! A `tokio_util::codec` framing of Base45 text.
+
+use std::io;
+
+use bytes::{BufMut, Bytes, BytesMut};
+use tokio_util::codec::{Decoder, Encoder};
+
+use crate::{Base45Error, ErrorKind, decode_bytes_in_place, encode_slice, encoded_len};
+
+/// How frames are separated on the wire.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Delimiter {
+    /// Text followed by `\n`; `\r\n` is accepted too.
+    Line,
+    /// A 4-byte big-endian character count followed by the text.
+    Length,
+}
+
+/// Bytes of the length prefix.
+const PREFIX: usize = 4;
+
+/// A `tokio_util` codec: each frame's bytes travel as one frame of Base45 text, ended by a line
+/// break or preceded by its length. Drop it into a `Framed` transport to exchange `Bytes`.
+///
+/// Decode failures are `io::Error`s of kind `InvalidData` wrapping the [`Base45Error`], positioned
+/// in the frame; text longer than [`with_max_length`](Base45Codec::with_max_length) fails with
+/// `TooLarge` before it is buffered whole.
+///
+/// ```
+/// use bytes::{Bytes, BytesMut};
+/// use qr_base45::tokio::Base45Codec;
+/// use tokio_util::codec::{Decoder, Encoder};
+///
+/// let mut codec = Base45Codec::lines();
+/// let mut wire = BytesMut::new();
+/// codec.encode(Bytes::from_static(b"ietf!"), &mut wire)?;
+/// assert_eq!(&wire[..], b"QED8WEX0\n");
+/// assert_eq!(codec.decode(&mut wire)?.unwrap(), &b"ietf!"[..]);
+/// # Ok::<(), std::io::Error>(())
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Base45Codec {
+    delimiter: Delimiter,
+    max_length: usize,
+    /// Bytes of the buffer already searched for a line break.
+    scanned: usize,
+}
+
+impl Base45Codec {
+    /// Frames of text ended by `\n`. A final frame without one is decoded at end of stream.
+    pub const fn lines() -> Base45Codec {
+        Base45Codec::new(Delimiter::Line)
+    }
+
+    /// Frames of text preceded by their length in characters as a 4-byte big-endian integer.
+    pub const fn length_prefixed() -> Base45Codec {
+        Base45Codec::new(Delimiter::Length)
+    }
+
+    const fn new(delimiter: Delimiter) -> Base45Codec {
+        Base45Codec {
+            delimiter,
+            max_length: u32::MAX as usize,
+            scanned: 0,
+        }
+    }
+
+    /// Refuse frames of more than `max_length` characters, in either direction. Unlimited (up to
+    /// what the length prefix can express) by default.
+    pub const fn with_max_length(mut self, max_length: usize) -> Base45Codec {
+        if max_length < u32::MAX as usize {
+            self.max_length = max_length;
+        }
+        self
+    }
+
+    /// The longest frame accepted, in characters.
+    pub const fn max_length(&self) -> usize {
+        self.max_length
+    }
+
+    fn too_large() -> io::Error {
+        Base45Error::new(ErrorKind::TooLarge).into()
+    }
+
+    /// Decode the text of one frame in place.
+    fn decode_frame(mut text: BytesMut) -> io::Result<Bytes> {
+        let len = decode_bytes_in_place(&mut text)?;
+        text.truncate(len);
+        Ok(text.freeze())
+    }
+}
+
+impl Default for Base45Codec {
+    /// [`Base45Codec::lines`].
+    fn default() -> Base45Codec {
+        Base45Codec::lines()
+    }
+}
+
+impl Encoder<Bytes> for Base45Codec {
+    type Error = io::Error;
+
+    fn encode(&mut self, item: Bytes, dst: &mut BytesMut) -> io::Result<()> {
+        let len = encoded_len(item.len());
+        if len > self.max_length {
+            return Err(Base45Codec::too_large());
+        }
+        dst.reserve(len + PREFIX);
+        if self.delimiter == Delimiter::Length {
+            dst.put_u32(len as u32);
+        }
+        let start = dst.len();
+        dst.resize(start + len, 0);
+        encode_slice(&item, &mut dst[start..])?;
+        if self.delimiter == Delimiter::Line {
+            dst.put_u8(b'\n');
+        }
+        Ok(())
+    }
+}
+
+impl Decoder for Base45Codec {
+    type Item = Bytes;
+    type Error = io::Error;
+
+    fn decode(&mut self, src: &mut BytesMut) -> io::Result<Option<Bytes>> {
+        match self.delimiter {
+            Delimiter::Line => {
+                let Some(i) = src[self.scanned..].iter().position(|&b| b == b'\n') else {
+                    self.scanned = src.len();
+                    if src.len() > self.max_length + 1 {
+                        return Err(Base45Codec::too_large());
+                    }
+                    return Ok(None);
+                };
+                let end = self.scanned + i;
+                self.scanned = 0;
+                let mut text = src.split_to(end + 1);
+                text.truncate(end);
+                if text.last() == Some(&b'\r') {
+                    text.truncate(end - 1);
+                }
+                if text.len() > self.max_length {
+                    return Err(Base45Codec::too_large());
+                }
+                Base45Codec::decode_frame(text).map(Some)
+            }
+            Delimiter::Length => {
+                let Some(prefix) = src.get(..PREFIX) else {
+                    return Ok(None);
+                };
+                let len = u32::from_be_bytes([prefix[0], prefix[1], prefix[2], prefix[3]]) as usize;
+                if len > self.max_length {
+                    return Err(Base45Codec::too_large());
+                }
+                if src.len() < PREFIX + len {
+                    src.reserve(PREFIX + len - src.len());
+                    return Ok(None);
+                }
+                let _ = src.split_to(PREFIX);
+                Base45Codec::decode_frame(src.split_to(len)).map(Some)
+            }
+        }
+    }
+
+    fn decode_eof(&mut self, src: &mut BytesMut) -> io::Result<Option<Bytes>> {
+        if let Some(frame) = self.decode(src)? {
+            return Ok(Some(frame));
+        }
+        match self.delimiter {
+            _ if src.is_empty() => Ok(None),
+            Delimiter::Line => {
+                self.scanned = 0;
+                let mut text = src.split();
+                if text.last() == Some(&b'\r') {
+                    text.truncate(text.len() - 1);
+                }
+                Base45Codec::decode_frame(text).map(Some)
+            }
+            Delimiter::Length => Err(io::ErrorKind::UnexpectedEof.into()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn frames(codec: &mut Base45Codec, wire: &[u8], step: usize) -> io::Result<Vec<Bytes>> {
+        let mut src = BytesMut::new();
+        let mut out = Vec::new();
+        for piece in wire.chunks(step) {
+            src.extend_from_slice(piece);
+            while let Some(frame) = codec.decode(&mut src)? {
+                out.push(frame);
+            }
+        }
+        while let Some(frame) = codec.decode_eof(&mut src)? {
+            out.push(frame);
+        }
+        Ok(out)
+    }
+
+    #[test]
+    fn frames_round_trip_in_any_split() {
+        let items: Vec<Bytes> = [&b"ietf!"[..], b"", b"\x00\xFF\x10", &[0x5A; 700]]
+            .into_iter()
+            .map(Bytes::copy_from_slice)
+            .collect();
+        for mut codec in [Base45Codec::lines(), Base45Codec::length_prefixed()] {
+            let mut wire = BytesMut::new();
+            for item in &items {
+                codec.encode(item.clone(), &mut wire).unwrap();
+            }
+            for step in [1, 2, 5, 4096] {
+                assert_eq!(frames(&mut codec, &wire, step).unwrap(), items);
+            }
+        }
+        // CRLF line endings, and a last line without one
+        let wire = b"QED8WEX0\r\nBB8\n%69 VD92EX0";
+        let out = frames(&mut Base45Codec::lines(), wire, 3).unwrap();
+        assert_eq!(out, [&b"ietf!"[..], b"AB", b"Hello!!"]);
+    }
+
+    #[test]
+    fn bad_frames_fail() {
+        let base45 = |e: io::Error| {
+            assert_eq!(e.kind(), io::ErrorKind::InvalidData);
+            let e = e.into_inner().unwrap().downcast::<Base45Error>().unwrap();
+            (e.kind, e.position)
+        };
+        let err = frames(&mut Base45Codec::lines(), b"QED8WEX0\nBB8a\n", 4).unwrap_err();
+        assert_eq!(base45(err), (ErrorKind::InvalidByte, 3));
+
+        let mut codec = Base45Codec::lines().with_max_length(8);
+        assert!(frames(&mut codec, b"QED8WEX0\r\n", 1).is_ok());
+        let err = frames(&mut codec, b"QED8WEX0BB8", 1).unwrap_err();
+        assert_eq!(base45(err).0, ErrorKind::TooLarge);
+        let err = codec.encode(Bytes::from_static(b"ietf!!"), &mut BytesMut::new());
+        assert_eq!(base45(err.unwrap_err()).0, ErrorKind::TooLarge);
+
+        let mut codec = Base45Codec::length_prefixed().with_max_length(8);
+        let err = frames(&mut codec, b"\0\0\0\x09", 4).unwrap_err();
+        assert_eq!(base45(err).0, ErrorKind::TooLarge);
+        let err = frames(&mut codec, b"\0\0\0\x03BB", 4).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::UnexpectedEof);
+    }
+}
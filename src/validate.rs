@@ -0,0 +1,98 @@
+//! Exhaustive validation that collects every problem instead of stopping at the first.
+
+use alloc::vec::Vec;
+use core::ops::Range;
+
+use crate::b45_val;
+
+/// Every problem found in a candidate Base45 string, as produced by [`validate`].
+/// Indices and ranges are byte offsets into the checked string.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct ValidationReport {
+    /// Each character outside the RFC 9285 alphabet, with its byte index.
+    pub invalid_chars: Vec<(usize, char)>,
+    /// The length leaves a single trailing character that cannot form a group.
+    pub dangling: bool,
+    /// Groups made of valid characters whose value exceeds the group's maximum
+    /// (65535 for 3 characters, 255 for the final 2).
+    pub overflowing_groups: Vec<Range<usize>>,
+}
+
+impl ValidationReport {
+    /// `true` if no problems were found, i.e. the string decodes successfully.
+    pub fn is_valid(&self) -> bool {
+        self.invalid_chars.is_empty() && !self.dangling && self.overflowing_groups.is_empty()
+    }
+}
+
+/// Check `s` and report all invalid characters, a dangling final character, and overflowing groups.
+pub fn validate(s: &str) -> ValidationReport {
+    let invalid_chars = s
+        .char_indices()
+        .filter(|&(_, ch)| u8::try_from(ch).ok().and_then(b45_val).is_none())
+        .collect();
+    let bytes = s.as_bytes();
+    let mut overflowing_groups = Vec::new();
+    for (k, group) in bytes.chunks(3).enumerate() {
+        let digit = |b| b45_val(b).map(u32::from);
+        let overflows = match *group {
+            [c0, c1, c2] => matches!(
+                (digit(c0), digit(c1), digit(c2)),
+                (Some(c0), Some(c1), Some(c2)) if c2 * 45 * 45 + c1 * 45 + c0 > 65535
+            ),
+            [c0, c1] => matches!(
+                (digit(c0), digit(c1)),
+                (Some(c0), Some(c1)) if c1 * 45 + c0 > 255
+            ),
+            _ => false,
+        };
+        if overflows {
+            overflowing_groups.push(k * 3..k * 3 + group.len());
+        }
+    }
+    ValidationReport {
+        invalid_chars,
+        dangling: bytes.len() % 3 == 1,
+        overflowing_groups,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reports_every_problem() {
+        assert!(validate("QED8WEX0").is_valid());
+        assert!(validate("").is_valid());
+
+        let report = validate("BB8:::a!BB8ZZ");
+        assert_eq!(report.invalid_chars, vec![(6, 'a'), (7, '!')]);
+        assert!(report.dangling);
+        assert_eq!(report.overflowing_groups, vec![3..6, 9..12]);
+
+        let report = validate("😀BB8ZZ");
+        assert_eq!(report.invalid_chars, vec![(0, '😀')]);
+        assert!(!report.dangling);
+        assert_eq!(report.overflowing_groups, vec![6..9]); // groups are byte-aligned: "8ZZ"
+        assert!(!report.is_valid());
+    }
+
+    #[test]
+    fn agrees_with_is_valid() {
+        for s in [
+            "",
+            "A",
+            "BB8",
+            "BB8A",
+            "ZZ",
+            ":::",
+            "bb8",
+            "FGW",
+            "GGW",
+            "%69 VD92EX0",
+        ] {
+            assert_eq!(validate(s).is_valid(), crate::is_valid(s), "{s:?}");
+        }
+    }
+}
@@ -0,0 +1,82 @@
+//! Checks that `encode_stream` and `decode_stream` run in bounded memory.
+//!
+//! A counting global allocator tracks the peak of live heap bytes. It lives in its own test binary
+//! with a single test, so no other test allocates alongside it.
+
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::io::{self, Read};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use qr_base45::{decode_stream, encode_stream};
+
+/// The system allocator, counting live bytes and their high-water mark.
+struct Counting;
+
+static LIVE: AtomicUsize = AtomicUsize::new(0);
+static PEAK: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for Counting {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let live = LIVE.fetch_add(layout.size(), Ordering::Relaxed) + layout.size();
+        PEAK.fetch_max(live, Ordering::Relaxed);
+        // SAFETY: forwarded with the caller's layout.
+        unsafe { System.alloc(layout) }
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        LIVE.fetch_sub(layout.size(), Ordering::Relaxed);
+        // SAFETY: `ptr` came from `alloc` with this layout.
+        unsafe { System.dealloc(ptr, layout) }
+    }
+}
+
+#[global_allocator]
+static ALLOC: Counting = Counting;
+
+/// Yields `left` bytes of `pattern`, repeated, without holding them.
+struct Repeat {
+    pattern: Vec<u8>,
+    left: u64,
+    at: usize,
+}
+
+impl Repeat {
+    fn new(unit: &[u8], len: u64) -> Repeat {
+        Repeat {
+            pattern: unit.repeat(4096),
+            left: len,
+            at: 0,
+        }
+    }
+}
+
+impl Read for Repeat {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let n = buf
+            .len()
+            .min(self.left as usize)
+            .min(self.pattern.len() - self.at);
+        buf[..n].copy_from_slice(&self.pattern[self.at..self.at + n]);
+        self.at = (self.at + n) % self.pattern.len();
+        self.left -= n as u64;
+        Ok(n)
+    }
+}
+
+#[test]
+fn streams_in_bounded_memory() {
+    const LEN: u64 = 16 << 20;
+    let mut buf = [0u8; 64 * 1024];
+    let mut input = Repeat::new(b"AB", LEN);
+    let mut text = Repeat::new(b"BB8", LEN / 2 * 3);
+    let base = LIVE.load(Ordering::Relaxed);
+    PEAK.store(base, Ordering::Relaxed);
+
+    let n = encode_stream(&mut input, &mut io::sink(), &mut buf).unwrap();
+    assert_eq!(n, LEN / 2 * 3);
+    let n = decode_stream(&mut text, &mut io::sink(), &mut buf).unwrap();
+    assert_eq!(n, LEN);
+    // Either direction holding its input or output would allocate at least 16 MiB
+    let extra = PEAK.load(Ordering::Relaxed) - base;
+    assert!(extra < 1 << 20, "{extra} bytes allocated");
+}